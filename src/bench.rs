@@ -0,0 +1,264 @@
+use crate::parse_flag;
+use clap::{Arg, ArgMatches, Command};
+use game_of_life::engine::Backend;
+use game_of_life::error::{Error, Result};
+use game_of_life::{Grid, Rule};
+use serde::Serialize;
+use std::time::Instant;
+
+/// Throughput of one backend on one random soup
+#[derive(Debug, Serialize)]
+struct Measurement {
+    backend: String,
+    width: usize,
+    height: usize,
+    density: f64,
+    threads: usize,
+    generations: u64,
+    seconds: f64,
+    generations_per_second: f64,
+    cells_per_second: f64,
+    /// Generations per second relative to the first measurement of the same grid size and
+    /// density
+    relative: f64,
+}
+
+/// `bench` subcommand, timing the simulation without opening a window
+pub fn command() -> Command<'static> {
+    Command::new("bench")
+        .about("Measure simulation throughput on random soups and print a comparison table")
+        .arg(
+            Arg::new("sizes")
+                .long("sizes")
+                .help("Comma separated grid sizes, as WIDTHxHEIGHT or a single number for square grids")
+                .value_name("sizes")
+                .takes_value(true)
+                .required(false)
+                .default_value("256,1024"),
+        )
+        .arg(
+            Arg::new("densities")
+                .long("densities")
+                .help("Comma separated fractions of cells alive at the start")
+                .value_name("densities")
+                .takes_value(true)
+                .required(false)
+                .default_value("0.35"),
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .help("Comma separated numbers of threads, 1 and every available core by default")
+                .value_name("threads")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("backends")
+                .long("backends")
+                .help("Comma separated backends: dense, sparse")
+                .value_name("backends")
+                .takes_value(true)
+                .required(false)
+                .default_value("dense,sparse"),
+        )
+        .arg(
+            Arg::new("generations")
+                .long("generations")
+                .help("Generations timed for each measurement")
+                .value_name("generations")
+                .takes_value(true)
+                .required(false)
+                .default_value("100"),
+        )
+        .arg(
+            Arg::new("rule")
+                .long("rule")
+                .help("Rule the soups evolve under")
+                .value_name("rule")
+                .takes_value(true)
+                .required(false)
+                .default_value("B3/S23"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed of the random soups")
+                .value_name("seed")
+                .takes_value(true)
+                .required(false)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the measurements as JSON instead of a table")
+                .takes_value(false)
+                .required(false),
+        )
+}
+
+/// Runs every combination of the options given to the `bench` subcommand and prints the
+/// results
+pub fn run(matches: &ArgMatches) -> Result<()> {
+    let sizes = parse_list("--sizes", matches.value_of("sizes").unwrap(), parse_size)?;
+    let densities = parse_list("--densities", matches.value_of("densities").unwrap(), |s| {
+        s.parse::<f64>()
+            .ok()
+            .filter(|density| (0.0..=1.0).contains(density))
+    })?;
+    let threads = match matches.value_of("threads") {
+        Some(threads) => parse_list("--threads", threads, |s| {
+            s.parse::<usize>().ok().filter(|&threads| threads > 0)
+        })?,
+        None => {
+            let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+            if available > 1 {
+                vec![1, available]
+            } else {
+                vec![1]
+            }
+        }
+    };
+    let backends = parse_list("--backends", matches.value_of("backends").unwrap(), |s| {
+        s.parse::<Backend>().ok()
+    })?;
+    let generations = parse_flag::<u64>(
+        "--generations",
+        matches.value_of("generations").unwrap(),
+        "a whole number of generations",
+    )?;
+    if generations == 0 {
+        return Err(Error::InvalidArgument {
+            name: "--generations".to_string(),
+            value: generations.to_string(),
+            expected: "at least 1 generation".to_string(),
+        });
+    }
+    let rule = matches.value_of("rule").unwrap().parse::<Rule>()?;
+    // Backends that can't run the rule are left out, rather than ending the whole run
+    let backends = backends
+        .into_iter()
+        .filter(
+            |backend| match backend.engine(Grid::with_rule(1, 1, rule.clone())) {
+                Ok(_) => true,
+                Err(err) => {
+                    eprintln!("skipping the {} backend: {}", backend, err);
+                    false
+                }
+            },
+        )
+        .collect::<Vec<Backend>>();
+    if backends.is_empty() {
+        return Err(Error::InvalidArgument {
+            name: "--backends".to_string(),
+            value: matches.value_of("backends").unwrap().to_string(),
+            expected: format!("a backend that can run {}", rule),
+        });
+    }
+    let seed = parse_flag::<u64>(
+        "--seed",
+        matches.value_of("seed").unwrap(),
+        "a whole number",
+    )?;
+
+    let mut measurements: Vec<Measurement> = vec![];
+    for &(width, height) in sizes.iter() {
+        for &density in densities.iter() {
+            let grid = Grid::with_rule(width, height, rule.clone()).with_random_soup(density, seed);
+            let mut baseline = None;
+            for &backend in backends.iter() {
+                for &thread_count in threads.iter() {
+                    let seconds = time(&grid, backend, thread_count, generations)?;
+                    let generations_per_second = generations as f64 / seconds;
+                    let baseline = *baseline.get_or_insert(generations_per_second);
+                    measurements.push(Measurement {
+                        backend: backend.to_string(),
+                        width,
+                        height,
+                        density,
+                        threads: thread_count,
+                        generations,
+                        seconds,
+                        generations_per_second,
+                        cells_per_second: generations_per_second * (width * height) as f64,
+                        relative: generations_per_second / baseline,
+                    });
+                    if !matches.is_present("json") {
+                        eprintln!(
+                            "{} {}x{} density {} with {} threads done",
+                            backend, width, height, density, thread_count
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    if matches.is_present("json") {
+        let json = serde_json::to_string_pretty(&measurements)
+            .map_err(|err| Error::Parse(format!("can't write measurements as JSON: {}", err)))?;
+        println!("{}", json);
+    } else {
+        print_table(&measurements);
+    }
+    Ok(())
+}
+
+/// Seconds `backend` takes to advance `grid` by `generations`, on a pool of `threads` threads
+fn time(grid: &Grid, backend: Backend, threads: usize, generations: u64) -> Result<f64> {
+    let mut engine = backend.engine(grid.clone())?;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|err| Error::InvalidArgument {
+            name: "--threads".to_string(),
+            value: threads.to_string(),
+            expected: format!("a number of threads that can be started ({})", err),
+        })?;
+    Ok(pool.install(|| {
+        let start = Instant::now();
+        engine.step_n(generations);
+        start.elapsed().as_secs_f64()
+    }))
+}
+
+fn print_table(measurements: &[Measurement]) {
+    println!(
+        "{:<8} {:>11} {:>8} {:>8} {:>12} {:>10} {:>14} {:>9}",
+        "backend", "size", "density", "threads", "generations", "gen/s", "cells/s", "relative"
+    );
+    for m in measurements {
+        println!(
+            "{:<8} {:>11} {:>8} {:>8} {:>12} {:>10.1} {:>14.3e} {:>8.2}x",
+            m.backend,
+            format!("{}x{}", m.width, m.height),
+            m.density,
+            m.threads,
+            m.generations,
+            m.generations_per_second,
+            m.cells_per_second,
+            m.relative
+        );
+    }
+}
+
+/// `WIDTHxHEIGHT`, or a single number for a square
+fn parse_size(s: &str) -> Option<(usize, usize)> {
+    let (width, height) = s.split_once('x').unwrap_or((s, s));
+    let size = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+    Some(size).filter(|&(width, height)| width > 0 && height > 0)
+}
+
+/// Comma separated values of an option, each read by `parse`
+fn parse_list<T>(name: &str, s: &str, parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>> {
+    s.split(',')
+        .map(|value| {
+            parse(value.trim()).ok_or_else(|| Error::InvalidArgument {
+                name: name.to_string(),
+                value: value.to_string(),
+                expected: "a comma separated list of valid values".to_string(),
+            })
+        })
+        .collect()
+}
@@ -1,17 +1,55 @@
 // Structs and Implementations
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Cell {
-    alive: bool,
+    /// 0 for dead cells, otherwise the colour of the live cell
+    state: u8,
+    /// Generation in which the cell last switched state, `None` if it never did
+    changed_at: Option<u64>,
 }
 
 impl Cell {
     pub fn new(alive: bool) -> Self {
-        Self { alive }
+        Self {
+            state: u8::from(alive),
+            changed_at: None,
+        }
+    }
+    /// Cell that switched to `state` during `generation`
+    pub fn changed(state: u8, generation: u64) -> Self {
+        Self {
+            state,
+            changed_at: Some(generation),
+        }
+    }
+    /// Cell in `state` that last switched state during `changed_at`
+    pub fn from_parts(state: u8, changed_at: Option<u64>) -> Self {
+        Self { state, changed_at }
     }
     pub fn is_alive(&self) -> bool {
-        self.alive
+        self.state != 0
+    }
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+    pub fn set_state(&mut self, state: u8) {
+        self.state = state;
+    }
+    /// Generation in which the cell last switched state, `None` if it never did
+    pub fn changed_at(&self) -> Option<u64> {
+        self.changed_at
+    }
+    /// Number of generations the cell has been alive, `None` if it is dead
+    pub fn age(&self, generation: u64) -> Option<u64> {
+        if !self.is_alive() {
+            return None;
+        }
+        Some(generation - self.changed_at.unwrap_or(0))
     }
-    pub fn set_state(&mut self, state: bool) {
-        self.alive = state;
+    /// Number of generations since the cell died, `None` if it is alive or never lived
+    pub fn dead_for(&self, generation: u64) -> Option<u64> {
+        if self.is_alive() {
+            return None;
+        }
+        self.changed_at.map(|changed_at| generation - changed_at)
     }
 }
@@ -0,0 +1,118 @@
+use game_of_life::cell::Cell;
+use game_of_life::rule::Rule;
+use ggez::graphics::Color;
+use std::str::FromStr;
+
+/// Age from which live cells get the last colour of the gradient
+const MAX_AGE: u64 = 64;
+/// Number of generations a dead cell leaves a trail
+const TRAIL_LENGTH: u64 = 16;
+
+/// Gradient used for the age of live cells, from newborn to old
+const AGE_GRADIENT: [(u64, (f32, f32, f32)); 4] = [
+    (0, (1.0, 1.0, 0.6)),       // Pale yellow
+    (4, (0.0, 0.8, 0.0)),       // Green
+    (16, (0.0, 0.6, 0.7)),      // Teal
+    (MAX_AGE, (0.2, 0.2, 0.9)), // Blue
+];
+
+/// Colours of the live cells of multicolour rules such as Immigration and QuadLife, repeated for
+/// the states of rule tables without colours of their own
+const SPECIES_COLORS: [(f32, f32, f32); 4] = [
+    (0.9, 0.2, 0.2), // Red
+    (0.2, 0.4, 1.0), // Blue
+    (0.2, 0.8, 0.2), // Green
+    (1.0, 0.9, 0.2), // Yellow
+];
+
+/// How cells are coloured when drawn
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorScheme {
+    /// Every live cell is green
+    Classic,
+    /// Live cells coloured by the number of generations they have been alive
+    Age,
+    /// Green live cells leaving fading trails where cells recently died
+    Trails,
+    /// Age gradient and trails combined
+    AgeTrails,
+}
+
+impl ColorScheme {
+    /// Colour of the cell at `generation`, `None` if nothing should be drawn
+    pub fn cell_color(&self, cell: &Cell, generation: u64) -> Option<Color> {
+        if let Some(age) = cell.age(generation) {
+            return match self {
+                ColorScheme::Classic | ColorScheme::Trails => Some(Color::new(0., 200., 0., 1.)), // Green
+                ColorScheme::Age | ColorScheme::AgeTrails => Some(age_color(age)),
+            };
+        }
+        match (self, cell.dead_for(generation)) {
+            (ColorScheme::Trails | ColorScheme::AgeTrails, Some(dead_for))
+                if dead_for < TRAIL_LENGTH =>
+            {
+                Some(trail_color(dead_for))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(ColorScheme::Classic),
+            "age" => Ok(ColorScheme::Age),
+            "trails" => Ok(ColorScheme::Trails),
+            "age-trails" => Ok(ColorScheme::AgeTrails),
+            _ => Err(format!("unknown color scheme '{}'", s)),
+        }
+    }
+}
+
+/// Colour drawn for live cells of the given colour of a multicolour rule, starting at 1
+fn species_color(colour: u8) -> Color {
+    let (r, g, b) = SPECIES_COLORS[(colour.max(1) as usize - 1) % SPECIES_COLORS.len()];
+    Color::new(r, g, b, 1.)
+}
+
+/// Colour of a live cell when it is decided by its state rather than the colour scheme, as for
+/// multicolour rules and rule tables
+pub fn state_color(rule: &Rule, state: u8) -> Option<Color> {
+    match rule {
+        Rule::Table(table) => Some(match table.color(state) {
+            Some((r, g, b)) => Color::from_rgb(r, g, b),
+            None => species_color(state),
+        }),
+        _ if rule.colours() > 1 => Some(species_color(state)),
+        _ => None,
+    }
+}
+
+/// Interpolates the age gradient
+fn age_color(age: u64) -> Color {
+    let age = age.min(MAX_AGE);
+    for stops in AGE_GRADIENT.windows(2) {
+        let (from_age, from) = stops[0];
+        let (to_age, to) = stops[1];
+        if age <= to_age {
+            let t = (age - from_age) as f32 / (to_age - from_age) as f32;
+            return Color::new(
+                from.0 + (to.0 - from.0) * t,
+                from.1 + (to.1 - from.1) * t,
+                from.2 + (to.2 - from.2) * t,
+                1.,
+            );
+        }
+    }
+    let (_, last) = AGE_GRADIENT[AGE_GRADIENT.len() - 1];
+    Color::new(last.0, last.1, last.2, 1.)
+}
+
+/// Dark red fading out as the cell stays dead
+fn trail_color(dead_for: u64) -> Color {
+    let fade = 1. - dead_for as f32 / TRAIL_LENGTH as f32;
+    Color::new(0.6 * fade, 0.1 * fade, 0.1 * fade, 1.)
+}
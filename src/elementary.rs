@@ -0,0 +1,90 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// One-dimensional two state rule: the next state of a cell depends on the cells within
+/// `radius` of it in the previous generation
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ElementaryRule {
+    /// Wolfram code of a radius 1 rule, bit `4 * left + 2 * centre + right` of the code is the
+    /// next state
+    Wolfram(u8),
+    /// Totalistic rule, bit `n` of the code is the next state of a cell with `n` live cells
+    /// among itself and its `2 * radius` neighbours
+    Totalistic { code: u64, radius: usize },
+}
+
+impl ElementaryRule {
+    pub fn radius(&self) -> usize {
+        match self {
+            ElementaryRule::Wolfram(_) => 1,
+            ElementaryRule::Totalistic { radius, .. } => *radius,
+        }
+    }
+    /// `window` holds the `2 * radius + 1` previous states centred on the cell
+    pub fn next_state(&self, window: &[bool]) -> bool {
+        match self {
+            ElementaryRule::Wolfram(code) => {
+                let idx = window
+                    .iter()
+                    .fold(0, |idx, &alive| idx * 2 + usize::from(alive));
+                code & (1 << idx) != 0
+            }
+            ElementaryRule::Totalistic { code, .. } => {
+                let alive = window.iter().filter(|&&alive| alive).count();
+                code & (1 << alive) != 0
+            }
+        }
+    }
+}
+
+impl fmt::Display for ElementaryRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElementaryRule::Wolfram(code) => write!(f, "W{}", code),
+            ElementaryRule::Totalistic { code, radius } => write!(f, "T{}R{}", code, radius),
+        }
+    }
+}
+
+impl FromStr for ElementaryRule {
+    type Err = String;
+
+    /// Parses `W<code>` for elementary rules such as `W110`, or `T<code>R<radius>` for
+    /// totalistic rules such as `T20R2`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rulestring = s.trim().to_uppercase();
+        if let Some(code) = rulestring.strip_prefix('W') {
+            let code = code
+                .parse::<u8>()
+                .map_err(|_| format!("Wolfram code of '{}' must be between 0 and 255", s))?;
+            return Ok(ElementaryRule::Wolfram(code));
+        }
+        let (code, radius) = rulestring
+            .strip_prefix('T')
+            .and_then(|rest| rest.split_once('R'))
+            .ok_or_else(|| format!("rule '{}' is not in W<code> or T<code>R<radius> form", s))?;
+        let radius = radius
+            .parse::<usize>()
+            .ok()
+            .filter(|radius| (1..=30).contains(radius))
+            .ok_or_else(|| format!("radius of '{}' must be between 1 and 30", s))?;
+        let code = code
+            .parse::<u64>()
+            .ok()
+            .filter(|&code| code >> (2 * radius + 2) == 0)
+            .ok_or_else(|| format!("code of '{}' is too large for its radius", s))?;
+        Ok(ElementaryRule::Totalistic { code, radius })
+    }
+}
+
+/// Whether a rulestring is in one of the forms parsed by `ElementaryRule`
+pub fn is_elementary_notation(s: &str) -> bool {
+    let rulestring = s.trim().to_uppercase();
+    let digits_after = |prefix: char| {
+        rulestring
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| c.is_ascii_digit())
+    };
+    digits_after('W') || digits_after('T')
+}
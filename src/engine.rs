@@ -0,0 +1,230 @@
+use crate::cell::Cell;
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::rule::Rule;
+use crate::scheme::UpdateScheme;
+use crate::sparse::SparseGrid;
+use crate::types::Point;
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// How the edges of a universe behave
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Topology {
+    /// Everything outside the universe is dead
+    Bounded,
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Topology::Bounded => write!(f, "bounded"),
+        }
+    }
+}
+
+/// A way of simulating a universe of cells. Callers go through this trait so they work the
+/// same whichever backend holds the cells.
+///
+/// Positions are those of a `width` by `height` universe, from its top-left corner. Engines can
+/// be moved to other threads, such as those of a rayon pool.
+pub trait LifeEngine: Send {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn topology(&self) -> Topology {
+        Topology::Bounded
+    }
+    fn rule(&self) -> &Rule;
+    /// Order cells are updated in, synchronous for backends without random schemes
+    fn scheme(&self) -> UpdateScheme {
+        UpdateScheme::Synchronous
+    }
+    /// Seed of the random update scheme
+    fn seed(&self) -> u64 {
+        0
+    }
+    /// Number of random numbers drawn so far, to restore the engine to the same point
+    fn rng_position(&self) -> u128 {
+        0
+    }
+    /// Number of steps taken since the universe was created
+    fn generation(&self) -> u64;
+    /// Cell at `pos`, `None` outside the universe
+    fn get_cell(&self, pos: Point) -> Option<Cell>;
+    /// Sets the state of the cell at `pos`
+    fn set_cell(&mut self, pos: Point, state: u8) -> Result<()>;
+    /// Sets the state of some cells, ignoring those outside the universe. Nothing changes if
+    /// any of the states isn't one of the rule
+    fn set_cells(&mut self, cells: &[(Point, u8)]) -> Result<()> {
+        for &(_, state) in cells.iter() {
+            self.rule().check_state(state)?;
+        }
+        for &(pos, state) in cells.iter() {
+            // Cells outside are skipped on purpose, so patterns can hang over the edge
+            let _ = self.set_cell(pos, state);
+        }
+        Ok(())
+    }
+    /// Copy of the engine in its current state
+    fn clone_engine(&self) -> Box<dyn LifeEngine>;
+    /// Every cell, row by row from the top-left corner
+    fn cells(&self) -> Cow<'_, [Cell]>;
+    /// Puts every cell back as given, along with the generation and the position of the
+    /// random number generator
+    fn restore(&mut self, cells: Vec<Cell>, generation: u64, rng_position: u128);
+    /// Advances the universe one generation
+    fn step(&mut self);
+    /// Advances the universe `count` generations
+    fn step_n(&mut self, count: u64) {
+        for _ in 0..count {
+            self.step();
+        }
+    }
+    /// Position and state of every live cell
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Point, u8)> + '_>;
+    /// Tiles recomputed by the last step and the number of tiles, for engines that skip the
+    /// areas where nothing changes
+    fn active_tiles(&self) -> Option<(usize, usize)> {
+        None
+    }
+    /// Number of live cells
+    fn population(&self) -> usize {
+        self.live_cells().count()
+    }
+    /// Top-left and bottom-right corners of the smallest rectangle holding every live cell,
+    /// `None` if there are none
+    fn bounding_box(&self) -> Option<(Point, Point)> {
+        self.live_cells()
+            .fold(None, |bounds, (pos, _)| match bounds {
+                None => Some((pos, pos)),
+                Some((min, max)) => Some((
+                    Point {
+                        x: min.x.min(pos.x),
+                        y: min.y.min(pos.y),
+                    },
+                    Point {
+                        x: max.x.max(pos.x),
+                        y: max.y.max(pos.y),
+                    },
+                )),
+            })
+    }
+}
+
+impl LifeEngine for Grid {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+    fn rule(&self) -> &Rule {
+        Grid::rule(self)
+    }
+    fn scheme(&self) -> UpdateScheme {
+        Grid::scheme(self)
+    }
+    fn seed(&self) -> u64 {
+        Grid::seed(self)
+    }
+    fn rng_position(&self) -> u128 {
+        Grid::rng_position(self)
+    }
+    fn generation(&self) -> u64 {
+        Grid::generation(self)
+    }
+    fn get_cell(&self, pos: Point) -> Option<Cell> {
+        self.cell(pos).cloned()
+    }
+    fn set_cell(&mut self, pos: Point, state: u8) -> Result<()> {
+        if pos.x >= Grid::width(self) || pos.y >= Grid::height(self) {
+            return Err(Error::OutOfBounds {
+                x: pos.x,
+                y: pos.y,
+                width: Grid::width(self),
+                height: Grid::height(self),
+            });
+        }
+        Grid::set_cells(self, &[(pos, state)])
+    }
+    fn set_cells(&mut self, cells: &[(Point, u8)]) -> Result<()> {
+        Grid::set_cells(self, cells)
+    }
+    fn clone_engine(&self) -> Box<dyn LifeEngine> {
+        Box::new(self.clone())
+    }
+    fn cells(&self) -> Cow<'_, [Cell]> {
+        Cow::Borrowed(Grid::cells(self))
+    }
+    fn restore(&mut self, cells: Vec<Cell>, generation: u64, rng_position: u128) {
+        Grid::restore(self, cells, generation, rng_position)
+    }
+    fn step(&mut self) {
+        Grid::step(self)
+    }
+    fn step_n(&mut self, count: u64) {
+        Grid::step_n(self, count)
+    }
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Point, u8)> + '_> {
+        Box::new(Grid::live_cells(self))
+    }
+    fn active_tiles(&self) -> Option<(usize, usize)> {
+        Some((Grid::active_tiles(self), Grid::tile_count(self)))
+    }
+}
+
+/// Engines that can be picked to run a simulation
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// Every cell stored in a `Grid`, supporting every rule and update scheme
+    #[default]
+    Dense,
+    /// Only live cells stored in a `SparseGrid`, for large universes with few live cells
+    Sparse,
+}
+
+impl Backend {
+    /// Every backend, the dense one first
+    pub const ALL: [Backend; 2] = [Backend::Dense, Backend::Sparse];
+
+    /// Engine of this backend in the state of `grid`
+    pub fn engine(&self, grid: Grid) -> Result<Box<dyn LifeEngine>> {
+        match self {
+            Backend::Dense => Ok(Box::new(grid)),
+            Backend::Sparse => {
+                if !grid.scheme().is_synchronous() {
+                    return Err(Error::InvalidArgument {
+                        name: "--scheme".to_string(),
+                        value: grid.scheme().to_string(),
+                        expected: "synchronous with the sparse backend".to_string(),
+                    });
+                }
+                let mut sparse = SparseGrid::with_rule(grid.width(), grid.height(), grid.rule())?;
+                sparse.restore(grid.cells().to_vec(), grid.generation(), 0);
+                Ok(Box::new(sparse))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Backend::Dense => write!(f, "dense"),
+            Backend::Sparse => write!(f, "sparse"),
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "dense" => Ok(Backend::Dense),
+            "sparse" => Ok(Backend::Sparse),
+            _ => Err(format!("unknown backend '{}'", s)),
+        }
+    }
+}
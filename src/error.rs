@@ -0,0 +1,85 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Everything that can go wrong setting up or loading a game
+#[derive(Debug)]
+pub enum Error {
+    /// A command line or settings value that can't be used, with what was expected instead
+    InvalidArgument {
+        name: String,
+        value: String,
+        expected: String,
+    },
+    /// A cell placed outside the grid
+    OutOfBounds {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// A starting pattern that isn't in the library
+    UnknownPattern(String),
+    /// A file that can't be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// Text that isn't a valid rule, pattern, snapshot or settings file
+    Parse(String),
+}
+
+impl Error {
+    /// Code the program exits with, 2 for mistakes in the arguments as is usual for the
+    /// command line
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidArgument { .. } | Error::UnknownPattern(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidArgument {
+                name,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value '{}' for {}, expected {}",
+                value, name, expected
+            ),
+            Error::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "cell ({}, {}) is outside the {}x{} grid",
+                x, y, width, height
+            ),
+            Error::UnknownPattern(name) => write!(f, "unknown pattern '{}'", name),
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Parse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Messages of the text parsers
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Parse(message)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
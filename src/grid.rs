@@ -1,83 +1,726 @@
 use crate::cell::Cell;
+use crate::elementary::ElementaryRule;
+use crate::error::{Error, Result};
+use crate::isotropic::IsotropicRule;
+use crate::ltl::{LtlNeighbourhood, LtlRule};
+use crate::margolus::MargolusRule;
+use crate::rule::{LifeRule, Rule, MAX_COLOURS};
+use crate::scheme::UpdateScheme;
+use crate::simd;
+use crate::table::RuleTable;
 use crate::types::Point;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
 
+/// Grids with fewer cells are updated on the calling thread, as handing the work out to other
+/// threads costs more than it saves
+const PARALLEL_THRESHOLD: usize = 128 * 128;
+/// Rows of cells updated together by one thread
+const TILE_ROWS: usize = 16;
+/// Side of the square tiles whose activity is tracked, so tiles where nothing can change are
+/// skipped
+const ACTIVE_TILE_SIZE: usize = 16;
+
+#[derive(Debug, Clone)]
 pub struct Grid {
     width: usize,
     height: usize,
-    pub cells: Vec<Cell>,
+    generation: u64,
+    rule: Rule,
+    /// Neighbourhood of the rule, cached to avoid rebuilding it for every cell
+    neighbour_offsets: Vec<(isize, isize)>,
+    scheme: UpdateScheme,
+    /// Seed of `rng`, kept so a run can be reproduced
+    seed: u64,
+    /// Source of randomness for the update scheme
+    rng: ChaCha8Rng,
+    pub(crate) cells: Vec<Cell>,
+    /// Tiles holding a cell that changed since the last step was computed, `None` when unknown
+    /// so every tile is recomputed
+    changed_tiles: Option<Vec<bool>>,
+    /// Number of tiles recomputed by the last step
+    active_tiles: usize,
+    /// Whether neighbours of life-like rules are counted with wide adders
+    simd: bool,
 }
 
 impl Grid {
     // Width and height of the Grid
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_rule(width, height, Rule::conway())
+    }
+    pub fn with_rule(width: usize, height: usize, rule: Rule) -> Self {
         Self {
             width,
             height,
+            generation: 0,
+            neighbour_offsets: match &rule {
+                Rule::Life(rule) => rule.neighbourhood().offsets(),
+                Rule::Table(table) => table.neighbourhood().offsets(),
+                Rule::LargerThanLife(_)
+                | Rule::Isotropic(_)
+                | Rule::Elementary(_)
+                | Rule::Margolus(_) => vec![],
+            },
+            rule,
+            scheme: UpdateScheme::Synchronous,
+            seed: 0,
+            rng: ChaCha8Rng::seed_from_u64(0),
             cells: vec![Cell::new(false); width * height],
+            changed_tiles: None,
+            active_tiles: 0,
+            simd: true,
+        }
+    }
+    /// Same grid updated with `scheme`, drawing random numbers from `seed`.
+    ///
+    /// One-dimensional rules always update synchronously. Margolus rules only take the
+    /// synchronous scheme, as updating cells on their own breaks the conservation of their
+    /// blocks.
+    pub fn with_scheme(mut self, scheme: UpdateScheme, seed: u64) -> Result<Self> {
+        if matches!(self.rule, Rule::Margolus(_)) && !scheme.is_synchronous() {
+            return Err(Error::InvalidArgument {
+                name: "--scheme".to_string(),
+                value: scheme.to_string(),
+                expected: "synchronous with Margolus rules".to_string(),
+            });
         }
+        self.scheme = scheme;
+        self.seed = seed;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        Ok(self)
+    }
+    /// Same grid counting the neighbours of life-like rules with wide adders if `simd` is set,
+    /// as it is by default, or one cell at a time otherwise
+    pub fn with_simd(mut self, simd: bool) -> Self {
+        self.simd = simd;
+        self
+    }
+    /// Same grid with each cell alive with probability `density`, in a random state of the
+    /// rule, drawing random numbers from `seed`. Panics if `density` isn't between 0 and 1
+    pub fn with_random_soup(mut self, density: f64, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let colours = self.rule.colours();
+        let cells = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Point { x, y }))
+            .filter(|_| rng.gen_bool(density))
+            .collect::<Vec<Point>>()
+            .into_iter()
+            .map(|pos| (pos, rng.gen_range(1..=colours)))
+            .collect::<Vec<(Point, u8)>>();
+        // Every cell is inside the grid
+        let _ = self.set_states(&cells);
+        self
+    }
+    /// Clears the grid and brings the given cells to life. Nothing changes if any of them is
+    /// outside the grid
+    pub fn set_state(&mut self, cells_coords: &[Point]) -> Result<()> {
+        let cells = cells_coords
+            .iter()
+            .map(|&pos| (pos, 1))
+            .collect::<Vec<(Point, u8)>>();
+        self.set_states(&cells)
     }
-    pub fn set_state(&mut self, cells_coords: &[Point]) {
+    /// Like `set_state`, giving each live cell its own state. Nothing changes either if any of
+    /// the states isn't one of the rule
+    pub fn set_states(&mut self, cells: &[(Point, u8)]) -> Result<()> {
+        for &(_, state) in cells.iter() {
+            self.rule.check_state(state)?;
+        }
+        if let Some(&(pos, _)) = cells
+            .iter()
+            .find(|(pos, _)| pos.x >= self.width || pos.y >= self.height)
+        {
+            return Err(Error::OutOfBounds {
+                x: pos.x,
+                y: pos.y,
+                width: self.width,
+                height: self.height,
+            });
+        }
         self.cells = vec![Cell::new(false); self.width * self.height];
-        for &pos in cells_coords.iter() {
+        self.changed_tiles = None;
+        for &(pos, state) in cells.iter() {
+            let idx = self.coords_to_index(pos);
+            self.cells[idx] = Cell::changed(state, self.generation);
+        }
+        Ok(())
+    }
+    /// Sets the state of some cells, leaving the others as they are. Cells outside the grid
+    /// are ignored, so patterns can be placed partly over the edge. Nothing changes if any of
+    /// the states isn't one of the rule
+    pub fn set_cells(&mut self, cells: &[(Point, u8)]) -> Result<()> {
+        for &(_, state) in cells.iter() {
+            self.rule.check_state(state)?;
+        }
+        for &(pos, state) in cells.iter() {
+            if pos.x >= self.width || pos.y >= self.height {
+                continue;
+            }
             let idx = self.coords_to_index(pos);
-            self.cells[idx].set_state(true);
+            if self.cells[idx].state() != state {
+                self.cells[idx] = Cell::changed(state, self.generation);
+                let tile = self.tile_of(idx);
+                if let Some(changed_tiles) = &mut self.changed_tiles {
+                    changed_tiles[tile] = true;
+                }
+            }
         }
+        Ok(())
+    }
+    /// Every cell, row by row from the top-left corner
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+    /// Cell at `pos`, `None` outside the grid
+    pub fn cell(&self, pos: Point) -> Option<&Cell> {
+        if pos.x >= self.width || pos.y >= self.height {
+            return None;
+        }
+        self.cells.get(self.coords_to_index(pos))
+    }
+    /// Position and state of every live cell, row by row from the top-left corner
+    pub fn live_cells(&self) -> impl Iterator<Item = (Point, u8)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_alive())
+            .map(|(idx, cell)| (self.index_to_coords(idx), cell.state()))
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Number of updates applied since the grid was created
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+    pub fn scheme(&self) -> UpdateScheme {
+        self.scheme
+    }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Number of random words the update scheme has drawn since it was seeded
+    pub fn rng_position(&self) -> u128 {
+        self.rng.get_word_pos()
+    }
+    /// Puts the grid back in an earlier or later state of the same run, `rng_position` being
+    /// what `Grid::rng_position` returned in that state
+    pub fn restore(&mut self, cells: Vec<Cell>, generation: u64, rng_position: u128) {
+        self.cells = cells;
+        self.changed_tiles = None;
+        self.generation = generation;
+        self.rng.set_word_pos(rng_position);
     }
-    fn cell_next_state(&self, cell_idx: usize) -> bool {
-        let cell = self.cells[cell_idx].clone();
+    /// Number of tiles of `ACTIVE_TILE_SIZE` squared cells covering the grid
+    pub fn tile_count(&self) -> usize {
+        self.width.div_ceil(ACTIVE_TILE_SIZE) * self.height.div_ceil(ACTIVE_TILE_SIZE)
+    }
+    /// Number of tiles recomputed by the last step. Every tile is while the changes of the
+    /// previous step aren't known, such as after the cells are replaced
+    pub fn active_tiles(&self) -> usize {
+        self.active_tiles
+    }
+    /// Tile holding the cell at `idx`
+    fn tile_of(&self, idx: usize) -> usize {
+        let pos = self.index_to_coords(idx);
+        (pos.y / ACTIVE_TILE_SIZE) * self.width.div_ceil(ACTIVE_TILE_SIZE)
+            + pos.x / ACTIVE_TILE_SIZE
+    }
+    /// Furthest neighbour of the rule along either axis
+    fn neighbour_radius(&self) -> usize {
+        self.neighbour_offsets
+            .iter()
+            .map(|&(x_off, y_off)| x_off.unsigned_abs().max(y_off.unsigned_abs()))
+            .max()
+            .unwrap_or(0)
+    }
+    /// Furthest a change can spread in one step, `None` if cells can change without any change
+    /// around them, as under random schemes or rules that depend on the generation
+    fn activity_radius(&self) -> Option<usize> {
+        if !self.scheme.is_synchronous() {
+            return None;
+        }
+        match &self.rule {
+            Rule::Life(_) | Rule::Table(_) => Some(self.neighbour_radius()),
+            Rule::Isotropic(_) => Some(1),
+            // Neighbour counts are summed over the whole grid at once, which costs less than
+            // counting those of the active tiles one by one
+            Rule::LargerThanLife(_) => None,
+            Rule::Elementary(_) | Rule::Margolus(_) => None,
+        }
+    }
+    /// Index of the neighbour at `offset` from `pos`, `None` if it is outside the grid
+    fn neighbour_index(&self, pos: Point, offset: (isize, isize)) -> Option<usize> {
+        let neighbour_coords = (pos.x as isize + offset.0, pos.y as isize + offset.1);
+        if neighbour_coords.0 < 0
+            || neighbour_coords.0 > self.width as isize - 1
+            || neighbour_coords.1 < 0
+            || neighbour_coords.1 > self.height as isize - 1
+        {
+            return None;
+        }
+        Some(self.coords_to_index(Point {
+            x: neighbour_coords.0 as usize,
+            y: neighbour_coords.1 as usize,
+        }))
+    }
+    fn cell_next_state(&self, rule: &LifeRule, cell_idx: usize) -> u8 {
+        let cell = &self.cells[cell_idx];
         let cell_pos = self.index_to_coords(cell_idx);
-        // Check boundaries and add neighgours
+        // Check boundaries and add neighgours, keeping track of their colours
         let mut num_neighbour_alive = 0;
-        for &x_off in [-1, 0, 1].iter() {
-            for &y_off in [-1, 0, 1].iter() {
-                if x_off == 0 && y_off == 0 {
-                    continue;
-                }
-
-                let neighbour_coords = (cell_pos.x as isize + x_off, cell_pos.y as isize + y_off);
-                if neighbour_coords.0 < 0
-                    || neighbour_coords.0 > self.width as isize - 1
-                    || neighbour_coords.1 < 0
-                    || neighbour_coords.1 > self.height as isize - 1
-                {
-                    continue;
-                }
-                let neighbour_pos = Point {
-                    x: neighbour_coords.0 as usize,
-                    y: neighbour_coords.1 as usize,
-                };
-                let idx = self.coords_to_index(neighbour_pos);
-                if self.cells[idx].is_alive() {
+        let mut colour_counts = [0; MAX_COLOURS + 1];
+        for &offset in self.neighbour_offsets.iter() {
+            if let Some(idx) = self.neighbour_index(cell_pos, offset) {
+                let state = self.cells[idx].state();
+                if state != 0 {
                     num_neighbour_alive += 1;
+                    colour_counts[(state as usize).min(MAX_COLOURS)] += 1;
                 }
             }
         }
 
         // Apply rules
-        if cell.is_alive() && (num_neighbour_alive >= 2 && num_neighbour_alive <= 3) {
-            return true; // alive
+        if !rule.next_state(cell.is_alive(), num_neighbour_alive) {
+            0
+        } else if cell.is_alive() {
+            cell.state()
+        } else {
+            rule.newborn_colour(&colour_counts)
         }
-        if !cell.is_alive() && num_neighbour_alive == 3 {
-            return true;
+    }
+    /// Next state under an isotropic non-totalistic rule, looked up from the configuration of
+    /// the cell and its 8 neighbours
+    fn isotropic_next_state(&self, rule: &IsotropicRule, cell_idx: usize) -> u8 {
+        let cell_pos = self.index_to_coords(cell_idx);
+        let mut config = 0;
+        // Bits go row by row from the north-west neighbour, the cell itself being bit 4
+        for (bit, offset) in (-1..=1)
+            .flat_map(|y_off| (-1..=1).map(move |x_off| (x_off, y_off)))
+            .enumerate()
+        {
+            match self.neighbour_index(cell_pos, offset) {
+                Some(idx) if self.cells[idx].is_alive() => config |= 1 << bit,
+                _ => {}
+            }
+        }
+        u8::from(rule.next_state(config))
+    }
+    /// Next state under a rule table, cells outside the grid being in state 0
+    fn table_next_state(&self, table: &RuleTable, cell_idx: usize) -> u8 {
+        let cell_pos = self.index_to_coords(cell_idx);
+        let mut states = Vec::with_capacity(self.neighbour_offsets.len() + 1);
+        states.push(self.cells[cell_idx].state());
+        for &offset in self.neighbour_offsets.iter() {
+            let state = self
+                .neighbour_index(cell_pos, offset)
+                .map_or(0, |idx| self.cells[idx].state());
+            states.push(state);
+        }
+        table.next_state(&states)
+    }
+    /// Next state under a Larger than Life rule, counting the neighbours one by one
+    fn ltl_next_state(&self, rule: &LtlRule, cell_idx: usize) -> u8 {
+        let cell_pos = self.index_to_coords(cell_idx);
+        let range = rule.range() as isize;
+        let num_neighbour_alive = (-range..=range)
+            .flat_map(|y_off| (-range..=range).map(move |x_off| (x_off, y_off)))
+            .filter(|&(x_off, y_off)| match rule.neighbourhood() {
+                LtlNeighbourhood::Moore => true,
+                LtlNeighbourhood::VonNeumann => x_off.abs() + y_off.abs() <= range,
+            })
+            .filter(|&offset| offset != (0, 0) || rule.includes_middle())
+            .filter(|&offset| {
+                self.neighbour_index(cell_pos, offset)
+                    .is_some_and(|idx| self.cells[idx].is_alive())
+            })
+            .count();
+        u8::from(rule.next_state(self.cells[cell_idx].is_alive(), num_neighbour_alive))
+    }
+    /// Next states under a Larger than Life rule. Neighbours are counted with a summed-area
+    /// table, so the cost per cell doesn't grow with the range
+    fn ltl_next_states(&self, rule: &LtlRule) -> Vec<u8> {
+        let stride = self.width + 1;
+        // Live cells in the rectangle from (0, 0) to (x, y), stored at (x + 1, y + 1)
+        let mut table = vec![0; stride * (self.height + 1)];
+        for y in 0..self.height {
+            let mut row_alive = 0;
+            for x in 0..self.width {
+                if self.cells[self.coords_to_index(Point { x, y })].is_alive() {
+                    row_alive += 1;
+                }
+                table[(y + 1) * stride + x + 1] = table[y * stride + x + 1] + row_alive;
+            }
         }
 
-        false
+        let range = rule.range();
+        self.next_states_by(|idx| {
+            let alive = self.cells[idx].is_alive();
+            let pos = self.index_to_coords(idx);
+            let (min_y, max_y) = (
+                pos.y.saturating_sub(range),
+                (pos.y + range).min(self.height - 1),
+            );
+            let mut num_neighbour_alive = match rule.neighbourhood() {
+                LtlNeighbourhood::Moore => {
+                    let (min_x, max_x) = (
+                        pos.x.saturating_sub(range),
+                        (pos.x + range).min(self.width - 1),
+                    );
+                    area_sum(&table, stride, (min_x, min_y), (max_x, max_y))
+                }
+                // Sum the diamond one row at a time
+                LtlNeighbourhood::VonNeumann => (min_y..=max_y)
+                    .map(|y| {
+                        let span = range - pos.y.abs_diff(y);
+                        let (min_x, max_x) = (
+                            pos.x.saturating_sub(span),
+                            (pos.x + span).min(self.width - 1),
+                        );
+                        area_sum(&table, stride, (min_x, y), (max_x, y))
+                    })
+                    .sum(),
+            };
+            if alive && !rule.includes_middle() {
+                num_neighbour_alive -= 1;
+            }
+            u8::from(rule.next_state(alive, num_neighbour_alive))
+        })
     }
-    pub fn update(&mut self) {
-        // Vector of next states. It will match by index
-        let next_states = (0..self.cells.len())
+    /// Next state under a Margolus block rule. Blocks start at even coordinates in even
+    /// generations and at odd ones in odd generations; blocks cut by the edge of the grid are
+    /// left unchanged
+    fn margolus_next_state(&self, rule: &MargolusRule, cell_idx: usize) -> u8 {
+        let cell_pos = self.index_to_coords(cell_idx);
+        let phase = (self.generation % 2) as usize;
+        // Top-left corner of the block, which is off the grid for the first row or column of
+        // odd generations
+        let (block_x, block_y) = (
+            ((cell_pos.x + phase) & !1) as isize - phase as isize,
+            ((cell_pos.y + phase) & !1) as isize - phase as isize,
+        );
+        let mut block = 0;
+        // Bits go row by row from the top-left cell of the block
+        for (bit, (x, y)) in [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().enumerate() {
+            let offset = (
+                block_x + x - cell_pos.x as isize,
+                block_y + y - cell_pos.y as isize,
+            );
+            match self.neighbour_index(cell_pos, offset) {
+                Some(idx) if self.cells[idx].is_alive() => block |= 1 << bit,
+                Some(_) => {}
+                None => return self.cells[cell_idx].state(),
+            }
+        }
+        let bit = (cell_pos.y as isize - block_y) * 2 + cell_pos.x as isize - block_x;
+        (rule.next_block(block) >> bit) & 1
+    }
+    /// Adds the next generation of a one-dimensional rule as a new row under the current one.
+    /// Once the grid is full the rows scroll up, so the grid holds the latest `height`
+    /// generations as a spacetime diagram
+    fn update_spacetime(&mut self, rule: ElementaryRule) {
+        let row = (self.generation as usize).min(self.height.saturating_sub(1));
+        let radius = rule.radius() as isize;
+        let next_row = (0..self.width)
             .into_par_iter()
-            .map(|idx| {
-                // next state
-                self.cell_next_state(idx)
+            .map(|x| {
+                let window = (-radius..=radius)
+                    .map(|x_off| {
+                        self.neighbour_index(Point { x, y: row }, (x_off, 0))
+                            .is_some_and(|idx| self.cells[idx].is_alive())
+                    })
+                    .collect::<Vec<bool>>();
+                rule.next_state(&window)
             })
             .collect::<Vec<bool>>();
 
-        self.cells = (0..self.cells.len())
-            .into_par_iter()
-            .map(|idx| Cell::new(next_states[idx]))
-            .collect::<Vec<Cell>>();
+        let generation = self.generation + 1;
+        if row + 1 == self.height {
+            self.cells.drain(..self.width);
+        } else {
+            self.cells.truncate((row + 1) * self.width);
+        }
+        self.cells
+            .extend(next_row.into_iter().map(|alive| match alive {
+                true => Cell::changed(1, generation),
+                false => Cell::new(false),
+            }));
+        self.cells
+            .resize(self.width * self.height, Cell::new(false));
+        self.generation = generation;
+    }
+    /// Updates the cells one at a time in a random order, each cell seeing the ones updated
+    /// before it
+    fn update_in_random_order(&mut self) {
+        let generation = self.generation + 1;
+        let mut order = (0..self.cells.len()).collect::<Vec<usize>>();
+        order.shuffle(&mut self.rng);
+        for idx in order {
+            let state = match &self.rule {
+                Rule::Life(rule) => self.cell_next_state(rule, idx),
+                Rule::LargerThanLife(rule) => self.ltl_next_state(rule, idx),
+                Rule::Isotropic(rule) => self.isotropic_next_state(rule, idx),
+                Rule::Table(table) => self.table_next_state(table, idx),
+                Rule::Elementary(_) | Rule::Margolus(_) => {
+                    unreachable!("rule can't update single cells")
+                }
+            };
+            if state != self.cells[idx].state() {
+                self.cells[idx] = Cell::changed(state, generation);
+            }
+        }
+        self.generation = generation;
+    }
+    /// Applies the randomness of the update scheme to the next states of a synchronous update
+    fn apply_scheme(&mut self, next_states: &mut [u8]) {
+        match self.scheme {
+            UpdateScheme::Synchronous | UpdateScheme::RandomOrder => {}
+            UpdateScheme::Asynchronous { alpha } => {
+                for (cell, next_state) in self.cells.iter().zip(next_states.iter_mut()) {
+                    if !self.rng.gen_bool(alpha) {
+                        *next_state = cell.state();
+                    }
+                }
+            }
+            UpdateScheme::Probabilistic { birth, survival } => {
+                for (cell, next_state) in self.cells.iter().zip(next_states.iter_mut()) {
+                    if *next_state == 0 {
+                        continue;
+                    }
+                    let probability = if cell.is_alive() { survival } else { birth };
+                    if !self.rng.gen_bool(probability) {
+                        *next_state = 0;
+                    }
+                }
+            }
+        }
+    }
+    /// Advances the grid `count` generations
+    pub fn step_n(&mut self, count: u64) {
+        for _ in 0..count {
+            self.step();
+        }
+    }
+    /// Advances the grid one generation
+    pub fn step(&mut self) {
+        if let Some(radius) = self.activity_radius() {
+            self.update_active_tiles(radius);
+            return;
+        }
+        self.changed_tiles = None;
+        self.active_tiles = self.tile_count();
+        if let Rule::Elementary(rule) = self.rule {
+            self.update_spacetime(rule);
+            return;
+        }
+        if self.scheme == UpdateScheme::RandomOrder {
+            self.update_in_random_order();
+            return;
+        }
+        // Vector of next states. It will match by index
+        let mut next_states = match &self.rule {
+            Rule::Life(rule) => self.next_states_by(|idx| self.cell_next_state(rule, idx)),
+            Rule::LargerThanLife(rule) => self.ltl_next_states(rule),
+            Rule::Isotropic(rule) => {
+                self.next_states_by(|idx| self.isotropic_next_state(rule, idx))
+            }
+            Rule::Table(table) => self.next_states_by(|idx| self.table_next_state(table, idx)),
+            Rule::Margolus(rule) => self.next_states_by(|idx| self.margolus_next_state(rule, idx)),
+            Rule::Elementary(_) => unreachable!("one-dimensional rules update a single row"),
+        };
+        self.apply_scheme(&mut next_states);
+
+        // Cells keep the generation they last changed in, so ages can be derived from it, and
+        // only those that change are written
+        let generation = self.generation + 1;
+        let write = |cells: &mut [Cell], next_states: &[u8]| {
+            for (cell, &next_state) in cells.iter_mut().zip(next_states) {
+                if cell.state() != next_state {
+                    *cell = Cell::changed(next_state, generation);
+                }
+            }
+        };
+        let tile = self.width * TILE_ROWS;
+        if self.cells.len() < PARALLEL_THRESHOLD {
+            write(&mut self.cells, &next_states);
+        } else {
+            self.cells
+                .par_chunks_mut(tile)
+                .zip(next_states.par_chunks(tile))
+                .for_each(|(cells, next_states)| write(cells, next_states));
+        }
+        self.generation = generation;
+    }
+    /// Recomputes only the tiles within `radius` cells of a change in the previous step, as the
+    /// cells of the others see the same neighbours as last time and so stay as they are
+    fn update_active_tiles(&mut self, radius: usize) {
+        let tiles_x = self.width.div_ceil(ACTIVE_TILE_SIZE);
+        let tiles_y = self.height.div_ceil(ACTIVE_TILE_SIZE);
+        let reach = radius.div_ceil(ACTIVE_TILE_SIZE);
+        let active = match &self.changed_tiles {
+            None => (0..tiles_x * tiles_y).collect::<Vec<usize>>(),
+            Some(changed_tiles) => (0..tiles_x * tiles_y)
+                .filter(|&tile| {
+                    let (tile_x, tile_y) = (tile % tiles_x, tile / tiles_x);
+                    (tile_y.saturating_sub(reach)..=(tile_y + reach).min(tiles_y - 1)).any(|y| {
+                        (tile_x.saturating_sub(reach)..=(tile_x + reach).min(tiles_x - 1))
+                            .any(|x| changed_tiles[y * tiles_x + x])
+                    })
+                })
+                .collect::<Vec<usize>>(),
+        };
+        let changes = match &self.rule {
+            // Counts are kept in bytes, so they can't go past 255 neighbours
+            Rule::Life(rule) if self.simd && self.neighbour_offsets.len() <= u8::MAX as usize => {
+                self.changes_in(&active, |tile| self.life_tile_changes(rule, tile))
+            }
+            Rule::Life(rule) => self.changes_in(&active, |tile| {
+                self.tile_cell_changes(tile, |idx| self.cell_next_state(rule, idx))
+            }),
+            Rule::Isotropic(rule) => self.changes_in(&active, |tile| {
+                self.tile_cell_changes(tile, |idx| self.isotropic_next_state(rule, idx))
+            }),
+            Rule::Table(table) => self.changes_in(&active, |tile| {
+                self.tile_cell_changes(tile, |idx| self.table_next_state(table, idx))
+            }),
+            _ => unreachable!("rule has no activity radius"),
+        };
+
+        let generation = self.generation + 1;
+        let mut changed_tiles = vec![false; tiles_x * tiles_y];
+        for (idx, state) in changes {
+            self.cells[idx] = Cell::changed(state, generation);
+            changed_tiles[self.tile_of(idx)] = true;
+        }
+        self.changed_tiles = Some(changed_tiles);
+        self.active_tiles = active.len();
+        self.generation = generation;
+    }
+    /// Changes of every tile of `tiles` found by `tile_changes`, the tiles being computed in
+    /// parallel when they hold enough cells
+    fn changes_in(
+        &self,
+        tiles: &[usize],
+        tile_changes: impl Fn(usize) -> Vec<(usize, u8)> + Sync,
+    ) -> Vec<(usize, u8)> {
+        if tiles.len() * ACTIVE_TILE_SIZE * ACTIVE_TILE_SIZE < PARALLEL_THRESHOLD {
+            tiles.iter().flat_map(|&tile| tile_changes(tile)).collect()
+        } else {
+            tiles
+                .par_iter()
+                .flat_map_iter(|&tile| tile_changes(tile))
+                .collect()
+        }
+    }
+    /// Top-left corner of `tile` and the corner past its bottom-right one
+    fn tile_bounds(&self, tile: usize) -> (Point, Point) {
+        let tiles_x = self.width.div_ceil(ACTIVE_TILE_SIZE);
+        let min = Point {
+            x: (tile % tiles_x) * ACTIVE_TILE_SIZE,
+            y: (tile / tiles_x) * ACTIVE_TILE_SIZE,
+        };
+        let max = Point {
+            x: (min.x + ACTIVE_TILE_SIZE).min(self.width),
+            y: (min.y + ACTIVE_TILE_SIZE).min(self.height),
+        };
+        (min, max)
+    }
+    /// Index and next state of the cells of `tile` whose state changes, computed one cell at a
+    /// time by `next_state`
+    fn tile_cell_changes(&self, tile: usize, next_state: impl Fn(usize) -> u8) -> Vec<(usize, u8)> {
+        let (min, max) = self.tile_bounds(tile);
+        (min.y..max.y)
+            .flat_map(|y| (min.x..max.x).map(move |x| y * self.width + x))
+            .filter_map(|idx| {
+                let state = next_state(idx);
+                (state != self.cells[idx].state()).then_some((idx, state))
+            })
+            .collect()
+    }
+    /// Like `tile_cell_changes` under a life-like rule, with the live neighbours of the whole
+    /// tile counted at once by adding shifted copies of it with wide adders
+    fn life_tile_changes(&self, rule: &LifeRule, tile: usize) -> Vec<(usize, u8)> {
+        let (min, max) = self.tile_bounds(tile);
+        let (width, height) = (max.x - min.x, max.y - min.y);
+        let radius = self.neighbour_radius();
+        // Whether each cell of the tile and the border around it is alive, cells outside the
+        // grid being dead. The padding at the end lets the last shifted copy run past the
+        // final row
+        let stride = width + 2 * radius;
+        let rows = height + 2 * radius;
+        let mut alive = vec![0; stride * rows + 2 * radius];
+        for row in 0..rows {
+            let Some(y) = (min.y + row)
+                .checked_sub(radius)
+                .filter(|&y| y < self.height)
+            else {
+                continue;
+            };
+            for column in 0..stride {
+                if let Some(x) = (min.x + column)
+                    .checked_sub(radius)
+                    .filter(|&x| x < self.width)
+                {
+                    alive[row * stride + column] =
+                        u8::from(self.cells[y * self.width + x].is_alive());
+                }
+            }
+        }
+        // Counts of the cells at (x, y) of the tile are at y * stride + x, the columns past the
+        // width of the tile holding counts that aren't used
+        let mut counts = vec![0; height * stride];
+        for &(x_off, y_off) in self.neighbour_offsets.iter() {
+            let start =
+                (radius as isize + y_off) as usize * stride + (radius as isize + x_off) as usize;
+            simd::add(&mut counts, &alive[start..start + height * stride]);
+        }
+
+        let mut changes = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                let idx = (min.y + y) * self.width + min.x + x;
+                let alive = self.cells[idx].is_alive();
+                match (
+                    alive,
+                    rule.next_state(alive, counts[y * stride + x] as usize),
+                ) {
+                    (true, false) => changes.push((idx, 0)),
+                    // Births are rare enough to find the colour of the newborn cell by itself
+                    (false, true) => changes.push((idx, self.cell_next_state(rule, idx))),
+                    _ => {}
+                }
+            }
+        }
+        changes
+    }
+    /// Next state of every cell given by `next_state` from the index of the cell. Large grids
+    /// are split into tiles of whole rows, updated in parallel on the current rayon pool
+    fn next_states_by(&self, next_state: impl Fn(usize) -> u8 + Sync) -> Vec<u8> {
+        let mut next_states = vec![0; self.cells.len()];
+        let tile = self.width * TILE_ROWS;
+        let fill = |first: usize, states: &mut [u8]| {
+            for (offset, state) in states.iter_mut().enumerate() {
+                *state = next_state(first + offset);
+            }
+        };
+        if self.cells.len() < PARALLEL_THRESHOLD {
+            fill(0, &mut next_states);
+        } else {
+            next_states
+                .par_chunks_mut(tile)
+                .enumerate()
+                .for_each(|(tile_idx, states)| fill(tile_idx * tile, states));
+        }
+        next_states
     }
     /// Converts a pair of cell coords to index in the cells vector
     pub fn coords_to_index(&self, pos: Point) -> usize {
@@ -87,8 +730,15 @@ impl Grid {
     /// Converts a index in the cells vecotr into pair of cell coords
     pub fn index_to_coords(&self, index: usize) -> Point {
         Point {
-            x: index % self.height,
+            x: index % self.width,
             y: index / self.width,
         }
     }
 }
+
+/// Sum of a summed-area table over the inclusive rectangle from `min` to `max`
+fn area_sum(table: &[usize], stride: usize, min: (usize, usize), max: (usize, usize)) -> usize {
+    table[(max.1 + 1) * stride + max.0 + 1] + table[min.1 * stride + min.0]
+        - table[min.1 * stride + max.0 + 1]
+        - table[(max.1 + 1) * stride + min.0]
+}
@@ -0,0 +1,229 @@
+use crate::cell::Cell;
+use crate::engine::LifeEngine;
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+/// Largest number of diffs between two keyframes, bounding the work of going back
+const KEYFRAME_INTERVAL: usize = 64;
+
+/// Cells of a recorded state
+#[derive(Debug, Clone)]
+enum Frame {
+    /// Every cell
+    Keyframe(Vec<Cell>),
+    /// Cells that differ from the previous frame, with their index
+    Diff(Vec<(usize, Cell)>),
+}
+
+impl Frame {
+    /// Approximate heap memory used by the frame, in bytes
+    fn memory(&self) -> usize {
+        match self {
+            Frame::Keyframe(cells) => cells.len() * size_of::<Cell>(),
+            Frame::Diff(changes) => changes.len() * size_of::<(usize, Cell)>(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    generation: u64,
+    rng_position: u128,
+    frame: Frame,
+}
+
+/// Timeline of the states a grid went through, for stepping backwards and forwards.
+///
+/// States are stored as keyframes holding every cell, followed by diffs holding only the cells
+/// that changed. Once the memory cap is reached the oldest states are dropped, a keyframe and
+/// its diffs at a time.
+#[derive(Debug, Clone)]
+pub struct History {
+    entries: VecDeque<Entry>,
+    /// Index of the entry the grid is in
+    position: usize,
+    /// Cells of the entry at `position`
+    current: Vec<Cell>,
+    /// Memory the entries may use, in bytes
+    memory_cap: usize,
+    memory_used: usize,
+}
+
+impl History {
+    /// History starting at the current state of `engine`, using about `memory_cap` bytes at
+    /// most
+    pub fn new(engine: &dyn LifeEngine, memory_cap: usize) -> Self {
+        let cells = engine.cells().into_owned();
+        let mut history = Self {
+            entries: VecDeque::new(),
+            position: 0,
+            current: cells.clone(),
+            memory_cap,
+            memory_used: 0,
+        };
+        history.push(
+            engine.generation(),
+            engine.rng_position(),
+            Frame::Keyframe(cells),
+        );
+        history
+    }
+    /// Number of recorded states
+    pub fn frame_count(&self) -> usize {
+        self.entries.len()
+    }
+    /// Index of the recorded state the grid is in, 0 being the oldest one kept
+    pub fn position(&self) -> usize {
+        self.position
+    }
+    /// Generations of the oldest and newest recorded states
+    pub fn generations(&self) -> (u64, u64) {
+        (
+            self.entries.front().map_or(0, |entry| entry.generation),
+            self.entries.back().map_or(0, |entry| entry.generation),
+        )
+    }
+    /// Records the state `engine` is in now. Any states after the current position are
+    /// discarded, as the engine has moved on from it in a different way
+    pub fn record(&mut self, engine: &dyn LifeEngine) {
+        let cells = engine.cells();
+        if cells.len() != self.current.len() {
+            self.truncate();
+            self.current = cells.into_owned();
+            let frame = Frame::Keyframe(self.current.clone());
+            self.push(engine.generation(), engine.rng_position(), frame);
+            return;
+        }
+        let changes = cells
+            .iter()
+            .zip(self.current.iter())
+            .enumerate()
+            .filter(|(_, (cell, current))| cell != current)
+            .map(|(idx, (cell, _))| (idx, cell.clone()))
+            .collect::<Vec<(usize, Cell)>>();
+        self.record_changes(engine.generation(), engine.rng_position(), changes);
+    }
+    /// Records the state reached from the current one by setting the cells of `changes`, each
+    /// with its index, such as the next generation computed by another engine. Any states after
+    /// the current position are discarded, as in `record`
+    pub fn record_changes(
+        &mut self,
+        generation: u64,
+        rng_position: u128,
+        changes: Vec<(usize, Cell)>,
+    ) {
+        self.truncate();
+        for (idx, cell) in changes.iter() {
+            self.current[*idx] = cell.clone();
+        }
+        let since_keyframe = self
+            .entries
+            .iter()
+            .rev()
+            .take_while(|entry| matches!(entry.frame, Frame::Diff(_)))
+            .count();
+        // Diffs of most of the grid take more room than a keyframe
+        let frame = if since_keyframe + 1 >= KEYFRAME_INTERVAL
+            || changes.len() * size_of::<(usize, Cell)>() >= self.current.len() * size_of::<Cell>()
+        {
+            Frame::Keyframe(self.current.clone())
+        } else {
+            Frame::Diff(changes)
+        };
+        self.push(generation, rng_position, frame);
+    }
+    /// Puts `engine` in the recorded state the grid is in, such as after recording changes
+    /// made elsewhere
+    pub fn restore(&self, engine: &mut dyn LifeEngine) {
+        let entry = &self.entries[self.position];
+        engine.restore(self.current.clone(), entry.generation, entry.rng_position);
+    }
+    /// Moves `engine` one state back, returning false if there is no earlier state
+    pub fn undo(&mut self, engine: &mut dyn LifeEngine) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.go_to(self.position - 1, engine);
+        true
+    }
+    /// Moves `engine` one state forward, returning false if it is in the newest state
+    pub fn redo(&mut self, engine: &mut dyn LifeEngine) -> bool {
+        if self.position + 1 >= self.entries.len() {
+            return false;
+        }
+        self.go_to(self.position + 1, engine);
+        true
+    }
+    /// Moves `engine` to the recorded state at `position`, clamped to the recorded states
+    pub fn go_to(&mut self, position: usize, engine: &mut dyn LifeEngine) {
+        let position = position.min(self.entries.len() - 1);
+        if position == self.position {
+            return;
+        }
+        match &self.entries[position].frame {
+            // Going forward by a diff only needs the diff
+            Frame::Diff(changes) if position == self.position + 1 => {
+                for (idx, cell) in changes.iter() {
+                    self.current[*idx] = cell.clone();
+                }
+            }
+            _ => self.current = self.reconstruct(position),
+        }
+        self.position = position;
+        let entry = &self.entries[position];
+        engine.restore(self.current.clone(), entry.generation, entry.rng_position);
+    }
+    /// Cells of the entry at `position`, from the last keyframe before it
+    fn reconstruct(&self, position: usize) -> Vec<Cell> {
+        let keyframe = (0..=position)
+            .rev()
+            .find(|&idx| matches!(self.entries[idx].frame, Frame::Keyframe(_)))
+            .expect("history starts with a keyframe");
+        let mut cells = match &self.entries[keyframe].frame {
+            Frame::Keyframe(cells) => cells.clone(),
+            Frame::Diff(_) => unreachable!(),
+        };
+        for entry in self.entries.range(keyframe + 1..=position) {
+            if let Frame::Diff(changes) = &entry.frame {
+                for (idx, cell) in changes.iter() {
+                    cells[*idx] = cell.clone();
+                }
+            }
+        }
+        cells
+    }
+    /// Drops the states after the current position
+    fn truncate(&mut self) {
+        while self.entries.len() > self.position + 1 {
+            if let Some(entry) = self.entries.pop_back() {
+                self.memory_used -= entry.frame.memory();
+            }
+        }
+    }
+    /// Adds a state after the others and moves to it
+    fn push(&mut self, generation: u64, rng_position: u128, frame: Frame) {
+        self.memory_used += frame.memory();
+        self.entries.push_back(Entry {
+            generation,
+            rng_position,
+            frame,
+        });
+        self.position = self.entries.len() - 1;
+        self.evict();
+    }
+    /// Drops the oldest keyframes and their diffs until the memory cap is met, keeping the
+    /// states from the last keyframe before the current position
+    fn evict(&mut self) {
+        while self.memory_used > self.memory_cap {
+            let next_keyframe = (1..=self.position)
+                .find(|&idx| matches!(self.entries[idx].frame, Frame::Keyframe(_)));
+            let Some(next_keyframe) = next_keyframe else {
+                break;
+            };
+            for entry in self.entries.drain(..next_keyframe) {
+                self.memory_used -= entry.frame.memory();
+            }
+            self.position -= next_keyframe;
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Bit of the cell itself in a neighbourhood configuration. Bits are laid out row by row from
+/// the north-west corner: NW = 1, N = 2, NE = 4, W = 8, centre = 16, E = 32, SW = 64, S = 128,
+/// SE = 256
+const CENTRE: usize = 16;
+
+/// Hensel letters for each number of live neighbours, counts above 4 reuse those of `8 - count`
+const LETTERS: [&str; 5] = ["", "ce", "ceaikn", "ceaikynqjr", "ceaikynqjrtwz"];
+
+/// One configuration of each letter in `LETTERS`, as drawn in Golly's Hensel notation
+const REPRESENTATIVES: [&[usize]; 5] = [
+    &[],
+    &[1, 2],
+    &[5, 10, 3, 40, 33, 68],
+    &[69, 42, 11, 7, 98, 97, 13, 70, 14, 41],
+    &[325, 170, 15, 45, 99, 101, 71, 102, 106, 43, 105, 78, 108],
+];
+
+/// Isotropic non-totalistic rule: the next state depends on the arrangement of the live cells
+/// among the 8 neighbours, up to rotation and reflection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsotropicRule {
+    /// Next state indexed by the 9 bit configuration of the cell and its neighbours
+    table: Vec<bool>,
+}
+
+impl IsotropicRule {
+    /// `config` holds the state of the cell and its neighbours as described by `CENTRE`
+    pub fn next_state(&self, config: usize) -> bool {
+        self.table[config]
+    }
+}
+
+impl fmt::Display for IsotropicRule {
+    /// Hensel notation, listing for each count the letters it applies to or, when shorter, `-`
+    /// and the letters it excludes
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letters = config_letters();
+        let conditions = |centre: usize| {
+            let mut conditions = String::new();
+            for count in 0..=8 {
+                let all = LETTERS[count.min(8 - count)];
+                let enabled = |letter: char| {
+                    letters.iter().enumerate().any(|(config, &config_letter)| {
+                        config & CENTRE == 0
+                            && config_letter == (count, letter)
+                            && self.table[config | centre]
+                    })
+                };
+                if all.is_empty() {
+                    if enabled(' ') {
+                        conditions.push_str(&count.to_string());
+                    }
+                    continue;
+                }
+                let included = all.chars().filter(|&c| enabled(c)).collect::<String>();
+                let excluded = all.chars().filter(|&c| !enabled(c)).collect::<String>();
+                if included.is_empty() {
+                    continue;
+                }
+                conditions.push_str(&count.to_string());
+                if excluded.is_empty() {
+                    continue;
+                }
+                if excluded.len() < included.len() {
+                    conditions.push('-');
+                    conditions.push_str(&excluded);
+                } else {
+                    conditions.push_str(&included);
+                }
+            }
+            conditions
+        };
+        write!(f, "B{}/S{}", conditions(0), conditions(CENTRE))
+    }
+}
+
+impl FromStr for IsotropicRule {
+    type Err = String;
+
+    /// Parses Hensel notation, where each count may be followed by the letters of the
+    /// configurations it applies to, or by `-` and the letters it excludes, e.g. `B2-a/S12`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.trim().split('/').collect::<Vec<&str>>();
+        if parts.len() != 2 {
+            return Err(format!("rule '{}' is not in B/S notation", s));
+        }
+        let (birth, survival) = match (split_prefix(parts[0]), split_prefix(parts[1])) {
+            (Some(('B', birth)), Some(('S', survival))) => (birth, survival),
+            (Some(('S', survival)), Some(('B', birth))) => (birth, survival),
+            _ => return Err(format!("rule '{}' is not in B/S notation", s)),
+        };
+        let letters = config_letters();
+        let birth = parse_conditions(birth, &letters)?;
+        let survival = parse_conditions(survival, &letters)?;
+        let table = (0..512)
+            .map(|config| {
+                if config & CENTRE != 0 {
+                    survival[config & !CENTRE]
+                } else {
+                    birth[config]
+                }
+            })
+            .collect::<Vec<bool>>();
+        Ok(Self { table })
+    }
+}
+
+/// Whether a B/S rulestring uses Hensel letters rather than plain counts
+pub fn is_hensel_notation(s: &str) -> bool {
+    s.trim().split('/').any(|part| {
+        part.starts_with(['B', 'b', 'S', 's'])
+            && part
+                .chars()
+                .skip(1)
+                .any(|c| c == '-' || LETTERS[4].contains(c))
+    })
+}
+
+/// Letter starting one half of a rulestring, in upper case, and the conditions after it.
+/// `None` if the half is empty
+fn split_prefix(part: &str) -> Option<(char, &str)> {
+    let mut chars = part.chars();
+    let prefix = chars.next()?.to_ascii_uppercase();
+    Some((prefix, chars.as_str()))
+}
+
+/// Parses one half of a rulestring into the set of enabled neighbour configurations
+fn parse_conditions(s: &str, letters: &[(usize, char)]) -> Result<Vec<bool>, String> {
+    let mut enabled = vec![false; 512];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let count = c
+            .to_digit(10)
+            .filter(|&count| count <= 8)
+            .ok_or_else(|| format!("invalid neighbour count '{}' in '{}'", c, s))?
+            as usize;
+        let negate = chars.next_if_eq(&'-').is_some();
+        let mut selected = vec![];
+        while let Some(letter) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            if !LETTERS[count.min(8 - count)].contains(letter) {
+                return Err(format!("count {} has no configuration '{}'", count, letter));
+            }
+            selected.push(letter);
+        }
+        for (config, &(config_count, letter)) in letters
+            .iter()
+            .enumerate()
+            .filter(|(config, _)| config & CENTRE == 0)
+        {
+            // Without letters every configuration with the count is enabled
+            let listed = selected.is_empty() || selected.contains(&letter);
+            if config_count == count && listed != negate {
+                enabled[config] = true;
+            }
+        }
+    }
+    Ok(enabled)
+}
+
+/// Number of live neighbours and Hensel letter of every configuration of the 8 neighbours,
+/// indexed by configuration without the centre bit
+fn config_letters() -> Vec<(usize, char)> {
+    let mut letters = vec![(0, ' '); 512];
+    for count in 0..=8 {
+        let base = count.min(8 - count);
+        for (letter, &representative) in LETTERS[base].chars().zip(REPRESENTATIVES[base]) {
+            // Configurations with more than 4 neighbours are the complement of those with less
+            let representative = if count > 4 {
+                !representative & 511 & !CENTRE
+            } else {
+                representative
+            };
+            for config in symmetries(representative) {
+                letters[config] = (count, letter);
+            }
+        }
+        if base == 0 {
+            let config = if count == 8 { 511 & !CENTRE } else { 0 };
+            letters[config] = (count, ' ');
+        }
+    }
+    letters
+}
+
+/// All rotations and reflections of a configuration
+fn symmetries(config: usize) -> Vec<usize> {
+    let transform = |config: usize, map: fn(usize, usize) -> (usize, usize)| {
+        (0..9)
+            .filter(|bit| config & (1 << bit) != 0)
+            .map(|bit| {
+                let (x, y) = map(bit % 3, bit / 3);
+                1 << (y * 3 + x)
+            })
+            .sum::<usize>()
+    };
+    let mut configs = vec![];
+    let mut rotated = config;
+    for _ in 0..4 {
+        rotated = transform(rotated, |x, y| (2 - y, x));
+        configs.push(rotated);
+        configs.push(transform(rotated, |x, y| (2 - x, y)));
+    }
+    configs
+}
@@ -1,4 +1,43 @@
+//! Cellular automaton engine behind the Game of Life window, usable on its own.
+//!
+//! A [`Grid`] holds the cells and the rule they follow, and is advanced with [`Grid::step`]:
+//!
+//! ```
+//! use game_of_life::{Grid, Pattern, Rule};
+//!
+//! let rule = "B3/S23".parse::<Rule>().unwrap();
+//! let mut grid = Grid::with_rule(20, 20, rule);
+//! let glider = Pattern::named("gliderA").unwrap();
+//! grid.set_states(glider.cells()).unwrap();
+//! grid.step_n(4);
+//! assert_eq!(grid.live_cells().count(), 5);
+//! ```
 pub mod cell;
+pub mod elementary;
+pub mod engine;
+pub mod error;
 pub mod grid;
-mod structs;
+pub mod history;
+pub mod isotropic;
+pub mod ltl;
+pub mod margolus;
+pub mod neighbourhood;
+pub mod pattern;
+pub mod rule;
+pub mod scheme;
+pub mod simd;
+pub mod simulation;
+pub mod snapshot;
+pub mod sparse;
+pub mod stats;
+pub mod structs;
+pub mod table;
 pub mod types;
+
+pub use crate::cell::Cell;
+pub use crate::engine::LifeEngine;
+pub use crate::error::{Error, Result};
+pub use crate::grid::Grid;
+pub use crate::pattern::Pattern;
+pub use crate::rule::Rule;
+pub use crate::types::Point;
@@ -0,0 +1,137 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Shape of a Larger than Life neighbourhood
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LtlNeighbourhood {
+    /// Square of side `2 * range + 1`
+    Moore,
+    /// Diamond of cells within `range` steps of the cell
+    VonNeumann,
+}
+
+/// Larger than Life rule: outer totalistic over a neighbourhood of any range, where births and
+/// survivals happen for a contiguous interval of counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LtlRule {
+    range: usize,
+    /// Whether the cell itself is part of its neighbourhood
+    middle: bool,
+    /// Inclusive interval of counts a live cell survives with
+    survival: (usize, usize),
+    /// Inclusive interval of counts a dead cell is born with
+    birth: (usize, usize),
+    neighbourhood: LtlNeighbourhood,
+}
+
+impl LtlRule {
+    pub fn new(
+        range: usize,
+        middle: bool,
+        survival: (usize, usize),
+        birth: (usize, usize),
+        neighbourhood: LtlNeighbourhood,
+    ) -> Self {
+        Self {
+            range,
+            middle,
+            survival,
+            birth,
+            neighbourhood,
+        }
+    }
+    pub fn range(&self) -> usize {
+        self.range
+    }
+    pub fn includes_middle(&self) -> bool {
+        self.middle
+    }
+    pub fn neighbourhood(&self) -> LtlNeighbourhood {
+        self.neighbourhood
+    }
+    /// `count` includes the cell itself when the middle is part of the neighbourhood
+    pub fn next_state(&self, alive: bool, count: usize) -> bool {
+        let (min, max) = if alive { self.survival } else { self.birth };
+        (min..=max).contains(&count)
+    }
+}
+
+impl fmt::Display for LtlRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "R{},C0,M{},S{}..{},B{}..{},N{}",
+            self.range,
+            u8::from(self.middle),
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            match self.neighbourhood {
+                LtlNeighbourhood::Moore => 'M',
+                LtlNeighbourhood::VonNeumann => 'N',
+            }
+        )
+    }
+}
+
+impl FromStr for LtlRule {
+    type Err = String;
+
+    /// Parses Larger than Life notation, e.g. Bosco's Rule `R5,C0,M1,S34..58,B34..45,NM`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = None;
+        let mut middle = false;
+        let mut survival = None;
+        let mut birth = None;
+        let mut neighbourhood = LtlNeighbourhood::Moore;
+        for part in s.trim().to_uppercase().split(',') {
+            let (key, value) = part.split_at(part.len().min(1));
+            match key {
+                "R" => range = Some(parse_number(value)?),
+                "C" => {
+                    // C0 and C2 are both two state rules
+                    if parse_number(value)? > 2 {
+                        return Err(format!("multi-state rule '{}' is not supported", s));
+                    }
+                }
+                "M" => middle = parse_number(value)? == 1,
+                "S" => survival = Some(parse_interval(value)?),
+                "B" => birth = Some(parse_interval(value)?),
+                "N" => {
+                    neighbourhood = match value {
+                        "M" => LtlNeighbourhood::Moore,
+                        "N" => LtlNeighbourhood::VonNeumann,
+                        _ => return Err(format!("unknown neighbourhood 'N{}'", value)),
+                    }
+                }
+                _ => return Err(format!("unexpected '{}' in rule '{}'", part, s)),
+            }
+        }
+        match (range, survival, birth) {
+            (Some(range), Some(survival), Some(birth)) if range > 0 => {
+                Ok(Self::new(range, middle, survival, birth, neighbourhood))
+            }
+            _ => Err(format!(
+                "rule '{}' needs a positive range and both S and B intervals",
+                s
+            )),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    s.parse::<usize>()
+        .map_err(|_| format!("invalid number '{}'", s))
+}
+
+/// Parses `min..max`, or a single count
+fn parse_interval(s: &str) -> Result<(usize, usize), String> {
+    match s.split_once("..") {
+        Some((min, max)) => Ok((parse_number(min)?, parse_number(max)?)),
+        None => {
+            let count = parse_number(s)?;
+            Ok((count, count))
+        }
+    }
+}
@@ -1,20 +1,40 @@
-mod cell;
-mod grid;
-mod structs;
-mod types;
+mod bench;
+mod colors;
+mod settings;
 
-use crate::grid::Grid;
-use crate::structs::*;
-use crate::types::Point;
-use clap::{Arg, Command};
+use crate::colors::{state_color, ColorScheme};
+use crate::settings::{Settings, DEFAULT_SETTINGS_FILE};
+use clap::{Arg, ArgMatches, Command};
+use game_of_life::engine::{Backend, LifeEngine};
+use game_of_life::error::{Error, Result};
+use game_of_life::grid::Grid;
+use game_of_life::history::History;
+use game_of_life::neighbourhood::Neighbourhood;
+use game_of_life::pattern::Pattern;
+use game_of_life::rule::{LifeRule, Rule};
+use game_of_life::scheme::UpdateScheme;
+use game_of_life::simulation::{Simulation, Speed};
+use game_of_life::snapshot;
+use game_of_life::stats::Statistics;
+use game_of_life::structs::PATTERNS;
+use game_of_life::table::RuleTable;
+use game_of_life::types::Point;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use ggez::event;
-use ggez::event::EventHandler;
+use ggez::event::{EventHandler, KeyCode, KeyMods, MouseButton};
 use ggez::graphics;
 use ggez::{Context, ContextBuilder, GameResult};
 use rand::Rng;
 
-const GRID: bool = false;
+/// Height of the timeline at the bottom of the window, in pixels
+const TIMELINE_HEIGHT: f32 = 12.;
+/// Width of the pattern palette on the right of the window, in pixels
+const PALETTE_WIDTH: f32 = 200.;
+/// Height of a row of the pattern palette, in pixels
+const PALETTE_ROW_HEIGHT: f32 = 18.;
 
 /// Config for the start of the game
 #[derive(Debug, Clone)]
@@ -23,386 +43,915 @@ pub struct Config {
     pub grid_height: usize,
     pub cell_size: f32,
     pub screen_size: (f32, f32),
+    pub fullscreen: bool,
+    /// Frames drawn per second
     pub fps: u32,
+    /// Generations computed per second while running
+    pub generation_rate: u32,
+    /// Whether generations are computed as fast as possible instead
+    pub max_speed: bool,
+    /// Whether the outline of every cell is drawn
+    pub grid_overlay: bool,
     pub initial_state: String,
+    pub color_scheme: ColorScheme,
+    pub rule: Rule,
+    pub scheme: UpdateScheme,
+    pub seed: u64,
+    /// Memory the history of past generations may use, in bytes
+    pub history_cap: usize,
+    /// File snapshots are saved to and loaded from in the window
+    pub snapshot_file: PathBuf,
+    pub backend: Backend,
+    /// Threads the grid is updated on, every available core if `None`
+    pub threads: Option<usize>,
 }
 
 struct MainState {
-    grid: Grid,
+    /// State shown on screen, edited and stepped through by hand. While running it follows
+    /// the snapshots of `simulation`
+    engine: Box<dyn LifeEngine>,
+    /// Copy of the engine stepped on a background thread while running
+    simulation: Simulation,
+    /// Tiles recomputed by the last step, by hand or in the background
+    active_tiles: Option<(usize, usize)>,
+    history: History,
+    /// Whether generations stop advancing on their own
+    paused: bool,
+    /// Whether the timeline is being dragged
+    scrubbing: bool,
+    /// Opposite corners of the selected cells
+    selection: Option<(Point, Point)>,
+    /// Whether the selection is being dragged out
+    selecting: bool,
+    /// Pattern being pasted, drawn under the mouse until it is placed
+    paste: Option<Pattern>,
+    /// Index in `PATTERNS` of the highlighted pattern while the palette is open
+    palette: Option<usize>,
+    /// Cell under the mouse
+    mouse_cell: Option<Point>,
+    /// System clipboard, `None` if it can't be reached
+    clipboard: Option<arboard::Clipboard>,
+    /// Last copied RLE, pasted when the system clipboard is unavailable
+    copied: String,
+    /// When the last frame was drawn, to keep to the frame rate
+    last_frame: Instant,
+    /// Colours of the cells on screen as RGBA bytes, kept between frames to reuse the memory
+    cell_pixels: Vec<u8>,
+    /// Image of the cells on screen. It is only rebuilt when `cell_image_key` changes or the
+    /// cells are edited, as edits keep the generation
+    cell_image: Option<graphics::Image>,
+    /// Generation, position in the history, size in pixels and cells per pixel `cell_image`
+    /// was drawn for
+    cell_image_key: (u64, usize, usize, usize, usize),
+    /// Outline of every cell, built the first time it is drawn as the layout doesn't change
+    grid_lines: Option<graphics::Mesh>,
     config: Config,
 }
 impl MainState {
-    pub fn new(_ctx: &mut Context, config: Config) -> Self {
+    pub fn new(_ctx: &mut Context, config: Config) -> Result<Self> {
         // Initialize the grid based on configuration
-        let mut grid = Grid::new(config.grid_width, config.grid_height);
+        let mut grid = Grid::with_rule(config.grid_width, config.grid_height, config.rule.clone())
+            .with_scheme(config.scheme, config.seed)?;
         // Initialize starting configuration
         let mut start_cells_coords: Vec<Point> = vec![];
+        let mut start_cells_states: Vec<(Point, u8)> = vec![];
         match &config.initial_state[..] {
-            "glider-gun" => {
-                start_cells_coords = GLIDER_GUN.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "toad" => {
-                start_cells_coords = TOAD.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "gliderA" => {
-                start_cells_coords = GLIDER_A.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "gliderB" => {
-                start_cells_coords = GLIDER_B.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "gliderC" => {
-                start_cells_coords = GLIDER_C.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "gliderD" => {
-                start_cells_coords = GLIDER_D.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "blinker" => {
-                start_cells_coords = BLINKER.iter().map(|&p| p.into()).collect::<Vec<Point>>();
-            }
-            "collisionAAI180" => {
-                start_cells_coords = GLIDER_COLLISION_AAI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionABI180" => {
-                start_cells_coords = GLIDER_COLLISION_ABI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionACI180" => {
-                start_cells_coords = GLIDER_COLLISION_ACI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionADI180" => {
-                start_cells_coords = GLIDER_COLLISION_ADI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBBI180" => {
-                start_cells_coords = GLIDER_COLLISION_BBI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBCI180" => {
-                start_cells_coords = GLIDER_COLLISION_BCI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBDI180" => {
-                start_cells_coords = GLIDER_COLLISION_BDI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionCCI180" => {
-                start_cells_coords = GLIDER_COLLISION_CCI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionCDI180" => {
-                start_cells_coords = GLIDER_COLLISION_CDI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionDDI180" => {
-                start_cells_coords = GLIDER_COLLISION_DDI180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionAAP180" => {
-                start_cells_coords = GLIDER_COLLISION_AAP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionABP180" => {
-                start_cells_coords = GLIDER_COLLISION_ABP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionACP180" => {
-                start_cells_coords = GLIDER_COLLISION_ACP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionADP180" => {
-                start_cells_coords = GLIDER_COLLISION_ADP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBBP180" => {
-                start_cells_coords = GLIDER_COLLISION_BBP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBCP180" => {
-                start_cells_coords = GLIDER_COLLISION_BCP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBDP180" => {
-                start_cells_coords = GLIDER_COLLISION_BDP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionCCP180" => {
-                start_cells_coords = GLIDER_COLLISION_CCP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionCDP180" => {
-                start_cells_coords = GLIDER_COLLISION_CDP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionDDP180" => {
-                start_cells_coords = GLIDER_COLLISION_DDP180
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionAAI90" => {
-                start_cells_coords = GLIDER_COLLISION_AAI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionABI90" => {
-                start_cells_coords = GLIDER_COLLISION_ABI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionACI90" => {
-                start_cells_coords = GLIDER_COLLISION_ACI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionADI90" => {
-                start_cells_coords = GLIDER_COLLISION_ADI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBBI90" => {
-                start_cells_coords = GLIDER_COLLISION_BBI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBCI90" => {
-                start_cells_coords = GLIDER_COLLISION_BCI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
-            }
-            "collisionBDI90" => {
-                start_cells_coords = GLIDER_COLLISION_BDI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            "random" => {
+                let mut rng = rand::thread_rng();
+                for i in 0..config.grid_width {
+                    for j in 0..config.grid_height {
+                        if rng.gen::<bool>() {
+                            start_cells_coords.push((i, j).into());
+                        }
+                    }
+                }
             }
-            "collisionCCI90" => {
-                start_cells_coords = GLIDER_COLLISION_CCI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            name => {
+                let pattern =
+                    Pattern::named(name).ok_or_else(|| Error::UnknownPattern(name.to_string()))?;
+                // Two state patterns are live cells, which multicolour rules split by colour
+                if pattern.cells().iter().all(|&(_, state)| state == 1) {
+                    start_cells_coords = pattern.cells().iter().map(|&(pos, _)| pos).collect();
+                } else {
+                    start_cells_states = pattern.cells().to_vec();
+                }
             }
-            "collisionCDI90" => {
-                start_cells_coords = GLIDER_COLLISION_CDI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        }
+        // One-dimensional rules start from the top row: a random row, otherwise a single cell
+        if config.rule.is_one_dimensional() {
+            start_cells_coords = match &config.initial_state[..] {
+                "random" => start_cells_coords
+                    .into_iter()
+                    .filter(|pos| pos.y == 0)
+                    .collect::<Vec<Point>>(),
+                _ => vec![(config.grid_width / 2, 0).into()],
+            };
+        }
+        // Convert the starting states into a vector of points
+        let colours = config.rule.colours();
+        if !start_cells_states.is_empty() {
+            grid.set_states(&start_cells_states)?;
+        } else if colours > 1 {
+            grid.set_states(&split_colours(&start_cells_coords, colours))?;
+        } else {
+            grid.set_state(&start_cells_coords)?;
+        }
+        let engine = config.backend.engine(grid)?;
+        Ok(Self::with_engine(config, engine))
+    }
+    /// State showing an engine that is already set up, such as one loaded from a snapshot
+    pub fn with_engine(config: Config, engine: Box<dyn LifeEngine>) -> Self {
+        let history = History::new(engine.as_ref(), config.history_cap);
+        let simulation = Simulation::start(engine.clone_engine());
+        let state = MainState {
+            engine,
+            simulation,
+            active_tiles: None,
+            history,
+            paused: false,
+            scrubbing: false,
+            selection: None,
+            selecting: false,
+            paste: None,
+            palette: None,
+            mouse_cell: None,
+            clipboard: arboard::Clipboard::new().ok(),
+            copied: String::new(),
+            last_frame: Instant::now(),
+            cell_pixels: vec![],
+            cell_image: None,
+            cell_image_key: (0, 0, 0, 0, 0),
+            grid_lines: None,
+            config,
+        };
+        state.simulation.run(state.speed());
+        state
+    }
+    /// Changes cells by hand, recording the change so it can be undone
+    fn edit(&mut self, cells: &[(Point, u8)]) {
+        if let Err(err) = self.engine.set_cells(cells) {
+            eprintln!("can't edit: {}", err);
+            return;
+        }
+        self.cell_image = None;
+        self.history.record(self.engine.as_ref());
+        if !self.paused {
+            self.simulation.restore(self.engine.as_ref());
+        }
+    }
+    /// Speed the simulation runs at
+    fn speed(&self) -> Speed {
+        match self.config.max_speed {
+            true => Speed::Max,
+            false => Speed::Rate(self.config.generation_rate),
+        }
+    }
+    /// Stops or starts the background simulation. It resumes from the state on screen, which
+    /// may have been edited or stepped through while paused
+    fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        if paused {
+            self.simulation.pause();
+        } else {
+            self.simulation.restore(self.engine.as_ref());
+            self.simulation.run(self.speed());
+        }
+    }
+    /// Cells of the selection, from the top-left corner
+    fn selected_cells(&self) -> Vec<Point> {
+        match self.selection {
+            Some((corner, opposite)) => (corner.y.min(opposite.y)..=corner.y.max(opposite.y))
+                .flat_map(|y| {
+                    (corner.x.min(opposite.x)..=corner.x.max(opposite.x))
+                        .map(move |x| Point { x, y })
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+    /// Puts the selection on the clipboard as RLE
+    fn copy_selection(&mut self) {
+        let Some((corner, opposite)) = self.selection else {
+            return;
+        };
+        let rle =
+            Pattern::from_engine(self.engine.as_ref(), corner, opposite).to_rle(self.engine.rule());
+        if let Some(clipboard) = &mut self.clipboard {
+            if let Err(err) = clipboard.set_text(rle.clone()) {
+                eprintln!("can't copy to the clipboard: {}", err);
             }
-            "collisionDDI90" => {
-                start_cells_coords = GLIDER_COLLISION_DDI90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        }
+        self.copied = rle;
+    }
+    fn clear_selection(&mut self) {
+        let cells = self
+            .selected_cells()
+            .into_iter()
+            .map(|pos| (pos, 0))
+            .collect::<Vec<(Point, u8)>>();
+        self.edit(&cells);
+    }
+    /// Gives each selected cell a random state, half of them being dead
+    fn fill_selection(&mut self) {
+        let mut rng = rand::thread_rng();
+        let colours = self.engine.rule().colours();
+        let cells = self
+            .selected_cells()
+            .into_iter()
+            .map(|pos| match rng.gen::<bool>() {
+                true => (pos, rng.gen_range(1..=colours)),
+                false => (pos, 0),
+            })
+            .collect::<Vec<(Point, u8)>>();
+        self.edit(&cells);
+    }
+    /// Starts placing the RLE pattern on the clipboard
+    fn start_paste(&mut self) {
+        let rle = self
+            .clipboard
+            .as_mut()
+            .and_then(|clipboard| clipboard.get_text().ok())
+            .unwrap_or_else(|| self.copied.clone());
+        // States are checked here, as the rule may have fewer than the pattern uses
+        let pattern = rle
+            .parse::<Pattern>()
+            .map_err(Error::from)
+            .and_then(|pattern| {
+                for &(_, state) in pattern.cells() {
+                    self.engine.rule().check_state(state)?;
+                }
+                Ok(pattern)
+            });
+        match pattern {
+            Ok(pattern) => self.paste = Some(pattern),
+            Err(err) => eprintln!("can't paste: {}", err),
+        }
+    }
+    /// Closes the palette and starts placing the pattern at `idx` in `PATTERNS`
+    fn pick_pattern(&mut self, idx: usize) {
+        self.paste = Pattern::named(PATTERNS[idx].0).map(|pattern| pattern.trimmed());
+        self.palette = None;
+    }
+    fn palette_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(
+            self.config.screen_size.0 - PALETTE_WIDTH,
+            0.,
+            PALETTE_WIDTH,
+            self.config.screen_size.1 - TIMELINE_HEIGHT,
+        )
+    }
+    /// Indices in `PATTERNS` of the rows shown in the palette, scrolled to keep the highlighted
+    /// one in view
+    fn palette_rows(&self, highlighted: usize) -> std::ops::Range<usize> {
+        let visible = ((self.palette_rect().h / PALETTE_ROW_HEIGHT) as usize).max(1);
+        let first = highlighted
+            .saturating_sub(visible / 2)
+            .min(PATTERNS.len().saturating_sub(visible));
+        first..(first + visible).min(PATTERNS.len())
+    }
+    /// Cell shown at a position of the window
+    fn cell_at(&self, x: f32, y: f32) -> Option<Point> {
+        let row = (y / self.config.cell_size).floor();
+        if row < 0. || row >= self.config.grid_height as f32 {
+            return None;
+        }
+        let mut column = x / self.config.cell_size;
+        if self.engine.rule().is_hexagonal() {
+            column -= (self.config.grid_height - 1 - row as usize) as f32 / 2.;
+        }
+        let column = column.floor();
+        if column < 0. || column >= self.config.grid_width as f32 {
+            return None;
+        }
+        Some(Point {
+            x: column as usize,
+            y: row as usize,
+        })
+    }
+    /// Replaces the grid with the one of a snapshot, starting a new history
+    fn load_snapshot(&mut self, path: &Path) -> Result<()> {
+        let grid = snapshot::load(path)?;
+        let engine = self.config.backend.engine(grid)?;
+        self.config.grid_width = engine.width();
+        self.config.grid_height = engine.height();
+        self.config.cell_size = layout_cell_size(
+            self.config.screen_size.0,
+            engine.width(),
+            engine.height(),
+            engine.rule(),
+        );
+        self.config.rule = engine.rule().clone();
+        self.config.scheme = engine.scheme();
+        self.config.seed = engine.seed();
+        self.history = History::new(engine.as_ref(), self.config.history_cap);
+        self.simulation = Simulation::start(engine.clone_engine());
+        self.engine = engine;
+        self.active_tiles = None;
+        // Built for the previous grid and size of the cells
+        self.cell_image = None;
+        self.grid_lines = None;
+        self.paused = true;
+        Ok(())
+    }
+    /// Moves to the next generation, replaying it from the history if it was already computed
+    fn step_forward(&mut self) {
+        if !self.history.redo(self.engine.as_mut()) {
+            self.engine.step();
+            self.history.record(self.engine.as_ref());
+            self.active_tiles = self.engine.active_tiles();
+        }
+    }
+    /// Moves to the recorded generation under `x` on the timeline
+    fn scrub_to(&mut self, x: f32) {
+        let last = self.history.frame_count() - 1;
+        let position = (x / self.config.screen_size.0 * last as f32)
+            .round()
+            .max(0.) as usize;
+        self.history.go_to(position, self.engine.as_mut());
+    }
+    /// Number of columns and rows of cells that fit on the screen, as those further right or
+    /// further down are never drawn
+    fn visible_cells(&self) -> (usize, usize) {
+        let (screen_width, screen_height) = self.config.screen_size;
+        let rows =
+            ((screen_height / self.config.cell_size).ceil() as usize).min(self.config.grid_height);
+        // Skewed hexagonal rows bring different columns on screen
+        let columns = if self.engine.rule().is_hexagonal() {
+            self.config.grid_width
+        } else {
+            ((screen_width / self.config.cell_size).ceil() as usize).min(self.config.grid_width)
+        };
+        (columns, rows)
+    }
+    /// Draws the cells on screen as a single image, scaled up to the size of the cells. Cells
+    /// smaller than a pixel are drawn a block per pixel, which takes the colour of its first
+    /// live cell
+    fn draw_cells(&mut self, ctx: &mut Context) -> GameResult {
+        let (columns, rows) = self.visible_cells();
+        // Side of the blocks of cells drawn as one pixel of the image, keeping the image within
+        // a pixel per screen pixel and the largest size of a texture
+        let max_side = u16::MAX as usize;
+        let step = ((1. / self.config.cell_size).ceil() as usize)
+            .max(columns.div_ceil(max_side))
+            .max(rows.div_ceil(max_side))
+            .max(1);
+        let (image_columns, image_rows) = (columns.div_ceil(step), rows.div_ceil(step));
+        let key = (
+            self.engine.generation(),
+            self.history.position(),
+            image_columns,
+            image_rows,
+            step,
+        );
+        let image = match &self.cell_image {
+            Some(image) if self.cell_image_key == key => image.clone(),
+            _ => {
+                let image = self.build_cell_image(ctx, (columns, rows), step)?;
+                self.cell_image = Some(image.clone());
+                self.cell_image_key = key;
+                image
             }
-            "collisionAAP90" => {
-                start_cells_coords = GLIDER_COLLISION_AAP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        };
+        let block_size = self.config.cell_size * step as f32;
+        let scale = ggez::mint::Vector2 {
+            x: block_size,
+            y: block_size,
+        };
+        if self.engine.rule().is_hexagonal() {
+            // A sprite per row of blocks, each skewed like the cells of its first row
+            let mut batch = graphics::spritebatch::SpriteBatch::new(image);
+            for block_y in 0..image_rows {
+                let first = self.cell_rect(Point {
+                    x: 0,
+                    y: block_y * step,
+                });
+                batch.add(
+                    graphics::DrawParam::default()
+                        .src(graphics::Rect::new(
+                            0.,
+                            block_y as f32 / image_rows as f32,
+                            1.,
+                            1. / image_rows as f32,
+                        ))
+                        .dest(ggez::mint::Point2 {
+                            x: first.x,
+                            y: first.y,
+                        })
+                        .scale(scale),
+                );
             }
-            "collisionABP90" => {
-                start_cells_coords = GLIDER_COLLISION_ABP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            graphics::draw(ctx, &batch, graphics::DrawParam::default())
+        } else {
+            graphics::draw(ctx, &image, graphics::DrawParam::default().scale(scale))
+        }
+    }
+    /// Image with a pixel per block of `step` by `step` cells, of the first `columns` and
+    /// `rows` of cells
+    fn build_cell_image(
+        &mut self,
+        ctx: &mut Context,
+        (columns, rows): (usize, usize),
+        step: usize,
+    ) -> GameResult<graphics::Image> {
+        let (image_columns, image_rows) = (columns.div_ceil(step), rows.div_ceil(step));
+        let generation = self.engine.generation();
+        let width = self.engine.width();
+        let cells = self.engine.cells();
+        self.cell_pixels.clear();
+        for block_y in 0..image_rows {
+            let block_rows = block_y * step..((block_y + 1) * step).min(rows);
+            for block_x in 0..image_columns {
+                let block_columns = block_x * step..((block_x + 1) * step).min(columns);
+                let mut block = block_rows.clone().flat_map(|y| {
+                    cells[y * width + block_columns.start..y * width + block_columns.end].iter()
+                });
+                let first = &cells[block_rows.start * width + block_columns.start];
+                let cell = block.find(|cell| cell.is_alive()).unwrap_or(first);
+                let color = match cell.is_alive() {
+                    true => state_color(self.engine.rule(), cell.state()),
+                    false => None,
+                }
+                .or_else(|| self.config.color_scheme.cell_color(cell, generation))
+                .unwrap_or(graphics::Color::BLACK);
+                let (r, g, b, a) = color.to_rgba();
+                self.cell_pixels.extend_from_slice(&[r, g, b, a]);
             }
-            "collisionACP90" => {
-                start_cells_coords = GLIDER_COLLISION_ACP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        }
+        // Both sides are at most `u16::MAX`, see `draw_cells`
+        let mut image = graphics::Image::from_rgba8(
+            ctx,
+            image_columns as u16,
+            image_rows as u16,
+            &self.cell_pixels,
+        )?;
+        image.set_filter(graphics::FilterMode::Nearest);
+        Ok(image)
+    }
+    /// Lines between the cells on screen: one per column and row, or for hexagonal layouts
+    /// two per row, the second one running along the sides of its cells
+    fn build_grid_lines(&self, ctx: &mut Context) -> GameResult<graphics::Mesh> {
+        let (columns, rows) = self.visible_cells();
+        let color = graphics::Color::new(10., 10., 10., 1.); // ?
+        let mut builder = graphics::MeshBuilder::new();
+        let size = self.config.cell_size;
+        let (left, right) = (
+            self.cell_rect(Point { x: 0, y: rows - 1 }).left(),
+            self.cell_rect(Point {
+                x: columns - 1,
+                y: 0,
+            })
+            .right(),
+        );
+        for y in 0..=rows {
+            let y = y as f32 * size;
+            builder.line(&[[left, y], [right, y]], 1., color)?;
+        }
+        if self.engine.rule().is_hexagonal() {
+            // A line per row, going down and up the sides of its cells in turn and along their
+            // tops and bottoms in between, which the lines between rows cover anyway
+            for y in 0..rows {
+                let sides = (0..=columns)
+                    .flat_map(|x| {
+                        let side = self.cell_rect(Point { x, y });
+                        match x % 2 {
+                            0 => [[side.x, side.y], [side.x, side.bottom()]],
+                            _ => [[side.x, side.bottom()], [side.x, side.y]],
+                        }
+                    })
+                    .collect::<Vec<[f32; 2]>>();
+                builder.line(&sides, 1., color)?;
             }
-            "collisionADP90" => {
-                start_cells_coords = GLIDER_COLLISION_ADP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        } else {
+            for x in 0..=columns {
+                let x = x as f32 * size;
+                builder.line(&[[x, 0.], [x, rows as f32 * size]], 1., color)?;
             }
-            "collisionBBP90" => {
-                start_cells_coords = GLIDER_COLLISION_BBP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        }
+        builder.build(ctx)
+    }
+    fn timeline_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(
+            0.,
+            self.config.screen_size.1 - TIMELINE_HEIGHT,
+            self.config.screen_size.0,
+            TIMELINE_HEIGHT,
+        )
+    }
+    /// Area of the screen covered by a cell
+    fn cell_rect(&self, pos: Point) -> graphics::Rect {
+        let mut x = pos.x as f32;
+        // Hexagonal rows are skewed half a cell to the left of the row above
+        if self.engine.rule().is_hexagonal() {
+            x += (self.config.grid_height - 1 - pos.y) as f32 / 2.;
+        }
+        graphics::Rect::new(
+            x * self.config.cell_size,
+            pos.y as f32 * self.config.cell_size,
+            self.config.cell_size,
+            self.config.cell_size,
+        )
+    }
+}
+
+impl EventHandler for MainState {
+    fn update(&mut self, _ctx: &mut Context) -> GameResult {
+        // Generations are computed in the background and all recorded, only the latest one is
+        // shown
+        if self.paused {
+            return Ok(());
+        }
+        let steps = self.simulation.take_steps();
+        let Some(active_tiles) = steps.last().map(|step| step.active_tiles) else {
+            return Ok(());
+        };
+        for step in steps {
+            self.history
+                .record_changes(step.generation, step.rng_position, step.changes);
+        }
+        self.history.restore(self.engine.as_mut());
+        self.active_tiles = active_tiles;
+        Ok(())
+    }
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        keymods: KeyMods,
+        _repeat: bool,
+    ) {
+        let ctrl = keymods.contains(KeyMods::CTRL);
+        if let Some(highlighted) = self.palette {
+            match keycode {
+                KeyCode::Up => self.palette = Some(highlighted.saturating_sub(1)),
+                KeyCode::Down => self.palette = Some((highlighted + 1).min(PATTERNS.len() - 1)),
+                KeyCode::Return => self.pick_pattern(highlighted),
+                KeyCode::P | KeyCode::Escape => self.palette = None,
+                _ => {}
             }
-            "collisionBCP90" => {
-                start_cells_coords = GLIDER_COLLISION_BCP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            return;
+        }
+        if let Some(pattern) = &self.paste {
+            let transformed = match keycode {
+                KeyCode::R => Some(pattern.rotate_clockwise()),
+                KeyCode::H => Some(pattern.flip_horizontal()),
+                KeyCode::V if !ctrl => Some(pattern.flip_vertical()),
+                _ => None,
+            };
+            if transformed.is_some() {
+                self.paste = transformed;
+                return;
             }
-            "collisionBDP90" => {
-                start_cells_coords = GLIDER_COLLISION_BDP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+        }
+        match keycode {
+            // Escape drops what is being pasted or selected before quitting
+            KeyCode::Escape if self.paste.is_some() => self.paste = None,
+            KeyCode::Escape if self.selection.is_some() => self.selection = None,
+            KeyCode::Escape => event::quit(ctx),
+            KeyCode::C if ctrl => self.copy_selection(),
+            KeyCode::X if ctrl => {
+                self.copy_selection();
+                self.clear_selection();
             }
-            "collisionCCP90" => {
-                start_cells_coords = GLIDER_COLLISION_CCP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            KeyCode::V if ctrl => self.start_paste(),
+            KeyCode::R if ctrl => self.fill_selection(),
+            KeyCode::Delete | KeyCode::Back => self.clear_selection(),
+            KeyCode::P => self.palette = Some(0),
+            KeyCode::Space => self.set_paused(!self.paused),
+            KeyCode::M => {
+                self.config.max_speed = !self.config.max_speed;
+                if !self.paused {
+                    self.simulation.run(self.speed());
+                }
             }
-            "collisionCDP90" => {
-                start_cells_coords = GLIDER_COLLISION_CDP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            // Stepping by hand pauses the simulation
+            KeyCode::Left => {
+                self.set_paused(true);
+                self.history.undo(self.engine.as_mut());
             }
-            "collisionDDP90" => {
-                start_cells_coords = GLIDER_COLLISION_DDP90
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            KeyCode::Right => {
+                self.set_paused(true);
+                self.step_forward();
             }
-            "100collisionAA" => {
-                start_cells_coords = GLIDER_100_COLLISION_AA
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            KeyCode::Home => {
+                self.set_paused(true);
+                self.history.go_to(0, self.engine.as_mut());
             }
-            "100collisionAC" => {
-                start_cells_coords = GLIDER_100_COLLISION_AC
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            KeyCode::End => {
+                self.set_paused(true);
+                let last = self.history.frame_count() - 1;
+                self.history.go_to(last, self.engine.as_mut());
             }
-            "100collisionAH1" => {
-                start_cells_coords = GLIDER_100_COLLISION_AH1
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            KeyCode::F5 => match snapshot::save(self.engine.as_ref(), &self.config.snapshot_file) {
+                Ok(()) => println!(
+                    "Saved generation {} to {}",
+                    self.engine.generation(),
+                    self.config.snapshot_file.display()
+                ),
+                Err(err) => eprintln!("{}", err),
+            },
+            KeyCode::F9 => {
+                let path = self.config.snapshot_file.clone();
+                if let Err(err) = self.load_snapshot(&path) {
+                    eprintln!("{}", err);
+                }
             }
-            "100collisionAH" => {
-                start_cells_coords = GLIDER_100_COLLISION_AH
-                    .iter()
-                    .map(|&p| p.into())
-                    .collect::<Vec<Point>>();
+            _ => {}
+        }
+    }
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if button != MouseButton::Left {
+            return;
+        }
+        if let (Some(highlighted), true) = (self.palette, self.palette_rect().contains([x, y])) {
+            let row = ((y - self.palette_rect().y) / PALETTE_ROW_HEIGHT) as usize;
+            if let Some(idx) = self.palette_rows(highlighted).nth(row) {
+                self.pick_pattern(idx);
             }
-            _ => {
-                let mut rng = rand::thread_rng();
-                for i in 0..config.grid_width {
-                    for j in 0..config.grid_height {
-                        if rng.gen::<bool>() {
-                            start_cells_coords.push((i, j).into());
-                        }
-                    }
+        } else if self.timeline_rect().contains([x, y]) {
+            self.set_paused(true);
+            self.scrubbing = true;
+            self.scrub_to(x);
+        } else if let Some(pos) = self.cell_at(x, y) {
+            match self.paste.take() {
+                Some(pattern) => self.edit(&pattern.placed_at(pos)),
+                None => {
+                    self.selection = Some((pos, pos));
+                    self.selecting = true;
                 }
             }
         }
-        // Convert the starting states into a vector of points
-        grid.set_state(&start_cells_coords);
-        MainState { grid, config }
     }
-}
-
-impl EventHandler for MainState {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        while ggez::timer::check_update_time(ctx, self.config.fps) {
-            self.grid.update();
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        if button == MouseButton::Left {
+            self.scrubbing = false;
+            // A click without dragging clears the selection
+            if self.selecting
+                && self
+                    .selection
+                    .is_some_and(|(corner, opposite)| corner == opposite)
+            {
+                self.selection = None;
+            }
+            self.selecting = false;
+        }
+    }
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        if self.scrubbing {
+            self.scrub_to(x);
+        }
+        self.mouse_cell = self.cell_at(x, y);
+        if let (true, Some((corner, _)), Some(pos)) =
+            (self.selecting, self.selection, self.mouse_cell)
+        {
+            self.selection = Some((corner, pos));
         }
-        Ok(())
     }
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
         graphics::clear(ctx, graphics::Color::BLACK);
-        // Mesh builder
-        let mut builder = graphics::MeshBuilder::new();
-        // Init, otherwise doesn't work for some reason
-        builder.rectangle(
-            graphics::DrawMode::fill(),
-            graphics::Rect::new(0., 0., 0., 0.),
-            graphics::Color::BLACK,
-        )?;
         // Draw cells
-        for (idx, cell) in self.grid.cells.iter().enumerate() {
-            if cell.is_alive() {
-                let pos = self.grid.index_to_coords(idx);
-                let color = graphics::Color::new(0., 200., 0., 1.); // Green
-                builder.rectangle(
-                    graphics::DrawMode::fill(),
-                    graphics::Rect::new(
-                        pos.x as f32 * self.config.cell_size,
-                        pos.y as f32 * self.config.cell_size,
-                        self.config.cell_size,
-                        self.config.cell_size,
-                    ),
-                    color,
-                )?;
+        self.draw_cells(ctx)?;
+        // Draw grid
+        if self.config.grid_overlay {
+            if self.grid_lines.is_none() {
+                self.grid_lines = Some(self.build_grid_lines(ctx)?);
+            }
+            if let Some(grid_lines) = &self.grid_lines {
+                graphics::draw(ctx, grid_lines, graphics::DrawParam::default())?;
             }
         }
-        // Draw grid
-        if GRID {
-            for idx in 0..self.grid.cells.len() {
-                let color = graphics::Color::new(10., 10., 10., 1.); // ?
-                let pos = self.grid.index_to_coords(idx);
-                builder.rectangle(
-                    graphics::DrawMode::stroke(1.),
-                    graphics::Rect::new(
-                        pos.x as f32 * self.config.cell_size,
-                        pos.y as f32 * self.config.cell_size,
-                        self.config.cell_size,
-                        self.config.cell_size,
-                    ),
-                    color,
+        // Mesh builder, for the paste and the selection
+        let mut builder = graphics::MeshBuilder::new();
+        let mut overlay = false;
+        // Pattern being pasted, under the mouse
+        if let (Some(pattern), Some(origin)) = (&self.paste, self.mouse_cell) {
+            for (pos, _) in pattern.placed_at(origin) {
+                if pos.x < self.config.grid_width && pos.y < self.config.grid_height {
+                    let color = graphics::Color::new(0.3, 0.8, 1., 0.6);
+                    builder.rectangle(graphics::DrawMode::fill(), self.cell_rect(pos), color)?;
+                    overlay = true;
+                }
+            }
+        }
+        // Outline of the selection
+        if let Some((corner, opposite)) = self.selection {
+            let first = self.cell_rect(Point {
+                x: corner.x.min(opposite.x),
+                y: corner.y.min(opposite.y),
+            });
+            let last = self.cell_rect(Point {
+                x: corner.x.max(opposite.x),
+                y: corner.y.max(opposite.y),
+            });
+            builder.rectangle(
+                graphics::DrawMode::stroke(1.),
+                graphics::Rect::new(
+                    first.x,
+                    first.y,
+                    last.right() - first.x,
+                    last.bottom() - first.y,
+                ),
+                graphics::Color::YELLOW,
+            )?;
+            overlay = true;
+        }
+        // Meshes can't be built without anything in them
+        if overlay {
+            let mesh = builder.build(ctx)?;
+            graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        }
+        // Timeline, filled up to the current generation
+        let timeline = self.timeline_rect();
+        let last = (self.history.frame_count() - 1).max(1);
+        let progress = self.history.position() as f32 / last as f32;
+        let timeline_mesh = graphics::MeshBuilder::new()
+            .rectangle(
+                graphics::DrawMode::fill(),
+                timeline,
+                graphics::Color::new(0.2, 0.2, 0.2, 1.),
+            )?
+            .rectangle(
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(timeline.x, timeline.y, timeline.w * progress, timeline.h),
+                graphics::Color::new(0.6, 0.6, 0.6, 1.),
+            )?
+            .build(ctx)?;
+        graphics::draw(ctx, &timeline_mesh, graphics::DrawParam::default())?;
+        // Pattern palette, with the highlighted pattern on a lighter row
+        if let Some(highlighted) = self.palette {
+            let panel = self.palette_rect();
+            let mut builder = graphics::MeshBuilder::new();
+            builder.rectangle(
+                graphics::DrawMode::fill(),
+                panel,
+                graphics::Color::new(0.1, 0.1, 0.1, 0.9),
+            )?;
+            for (row, idx) in self.palette_rows(highlighted).enumerate() {
+                let y = panel.y + row as f32 * PALETTE_ROW_HEIGHT;
+                if idx == highlighted {
+                    builder.rectangle(
+                        graphics::DrawMode::fill(),
+                        graphics::Rect::new(panel.x, y, panel.w, PALETTE_ROW_HEIGHT),
+                        graphics::Color::new(0.3, 0.3, 0.5, 1.),
+                    )?;
+                }
+            }
+            let palette_mesh = builder.build(ctx)?;
+            graphics::draw(ctx, &palette_mesh, graphics::DrawParam::default())?;
+            for (row, idx) in self.palette_rows(highlighted).enumerate() {
+                let position = ggez::mint::Point2 {
+                    x: panel.x + 5.,
+                    y: panel.y + row as f32 * PALETTE_ROW_HEIGHT + 2.,
+                };
+                graphics::draw(
+                    ctx,
+                    &graphics::Text::new(PATTERNS[idx].0),
+                    (position, graphics::Color::WHITE),
                 )?;
             }
         }
-        let mesh = builder.build(ctx)?;
-        // Draw
-        graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+        // Statistics
+        let stats = Statistics {
+            active_tiles: self.active_tiles,
+            ..Statistics::new(self.engine.as_ref())
+        };
+        let mut text = format!(
+            "Generation {}  Population {}",
+            stats.generation, stats.population
+        );
+        if let Some((active, tiles)) = stats.active_tiles {
+            text.push_str(&format!("  Active tiles {}/{}", active, tiles));
+        }
+        // The seed is needed to reproduce a run with a random update scheme
+        if !self.engine.scheme().is_synchronous() {
+            text.push_str(&format!("  Seed {}", self.engine.seed()));
+        }
+        if stats.colour_populations.len() > 1 {
+            let label = match self.engine.rule() {
+                Rule::Table(_) => "State",
+                _ => "Colour",
+            };
+            for (colour, population) in stats.colour_populations.iter().enumerate() {
+                text.push_str(&format!("  {} {}: {}", label, colour + 1, population));
+            }
+        }
+        if let Some(pattern) = &self.paste {
+            text.push_str(&format!(
+                "  Pasting {}x{}: click to place, R to rotate, H and V to flip",
+                pattern.width(),
+                pattern.height()
+            ));
+        }
+        if self.config.max_speed {
+            text.push_str("  Max speed");
+        }
+        if self.paused {
+            let (first, last) = self.history.generations();
+            text.push_str(&format!("  Paused (history {}-{})", first, last));
+        }
+        graphics::draw(
+            ctx,
+            &graphics::Text::new(text),
+            (ggez::mint::Point2 { x: 5., y: 5. }, graphics::Color::WHITE),
+        )?;
         // Present on screen
         graphics::present(ctx)?;
+        // Wait out the rest of the frame to keep to the frame rate
+        let frame = Duration::from_secs_f64(1. / self.config.fps as f64);
+        if let Some(rest) = frame.checked_sub(self.last_frame.elapsed()) {
+            ggez::timer::sleep(rest);
+        }
+        self.last_frame = Instant::now();
         Ok(())
     }
 }
 
+/// Gives the cells of each side of the starting pattern their own colour: left and right halves
+/// for 2 colours, quadrants for more
+fn split_colours(cells_coords: &[Point], colours: u8) -> Vec<(Point, u8)> {
+    let min_x = cells_coords.iter().map(|pos| pos.x).min().unwrap_or(0);
+    let max_x = cells_coords.iter().map(|pos| pos.x).max().unwrap_or(0);
+    let min_y = cells_coords.iter().map(|pos| pos.y).min().unwrap_or(0);
+    let max_y = cells_coords.iter().map(|pos| pos.y).max().unwrap_or(0);
+    cells_coords
+        .iter()
+        .map(|&pos| {
+            let right = pos.x * 2 > min_x + max_x;
+            let bottom = pos.y * 2 > min_y + max_y;
+            let colour = match (colours, right, bottom) {
+                (2, right, _) => 1 + u8::from(right),
+                (_, right, bottom) => 1 + u8::from(right) + 2 * u8::from(bottom),
+            };
+            (pos, colour.min(colours))
+        })
+        .collect()
+}
+
+/// Size of a cell when a grid fills the width of the screen
+fn layout_cell_size(screen_width: f32, grid_width: usize, grid_height: usize, rule: &Rule) -> f32 {
+    // Hexagonal layouts are wider by half a cell per row
+    let layout_width = if rule.is_hexagonal() {
+        grid_width as f32 + grid_height as f32 / 2.
+    } else {
+        grid_width as f32
+    };
+    screen_width / layout_width
+}
+
 fn main() -> GameResult {
-    // CLI
-    let matches = Command::new("Game of Life")
+    let matches = cli().get_matches();
+    if let Some(bench_matches) = matches.subcommand_matches("bench") {
+        bench::run(bench_matches).unwrap_or_else(|err| exit_with(err));
+        return Ok(());
+    }
+    let (mut config, loaded) = configure(&matches).unwrap_or_else(|err| exit_with(err));
+    if let Some(threads) = config.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|err| Error::InvalidArgument {
+                name: "--threads".to_string(),
+                value: threads.to_string(),
+                expected: format!("a number of threads that can be started ({})", err),
+            })
+            .unwrap_or_else(|err| exit_with(err));
+    }
+
+    // Setup ggez stuff
+    let mut window_mode =
+        ggez::conf::WindowMode::default().dimensions(config.screen_size.0, config.screen_size.1);
+    if config.fullscreen {
+        window_mode = window_mode.fullscreen_type(ggez::conf::FullscreenType::Desktop);
+    }
+    let cb = ContextBuilder::new("Game of life", "J. Rene H.S.").window_mode(window_mode);
+    let (mut ctx, event_loop) = cb.build()?;
+    graphics::set_window_title(&ctx, "Game of life");
+    // Fullscreen windows take the size of the display, so the cells are laid out again
+    if config.fullscreen {
+        config.screen_size = graphics::drawable_size(&ctx);
+        let (width, height) = config.screen_size;
+        graphics::set_screen_coordinates(&mut ctx, graphics::Rect::new(0., 0., width, height))?;
+        config.cell_size =
+            layout_cell_size(width, config.grid_width, config.grid_height, &config.rule);
+    }
+    // Setup game state -> game loop
+    let state = match loaded {
+        Some(grid) => config
+            .backend
+            .engine(grid)
+            .map(|engine| MainState::with_engine(config, engine)),
+        None => MainState::new(&mut ctx, config),
+    }
+    .unwrap_or_else(|err| exit_with(err));
+
+    event::run(ctx, event_loop, state);
+}
+
+/// Command line options of the game, and its subcommands
+fn cli() -> Command<'static> {
+    Command::new("Game of Life")
         .version("0.1")
         .author("J. Rene H.S.")
         .arg(
@@ -429,43 +978,360 @@ fn main() -> GameResult {
             Arg::new("initial_state")
                 .short('s')
                 .long("initial-state")
-                .help(
-                    "Initial state options: blinker, toad, gliderA, gliderB, gliderC, gliderD, glider-gun, random, collisionAA180, collisionAB180, collisionAC180, collisionAD180, collisionBB180, collisionBC180, collisionBD180, collisionCC180, collisionCD180, collisionDD180, collisionAA90, collisionAB90, collisionAC90, collisionAD90, collisionBB90, collisionBC90, collisionBD90, collisionCC90, collisionCD90, collisionDD90, 100collisionAA , 100collisionAC , 100collisionAH, 100collisionAH1",
-                )
+                .help("Initial state, random or a pattern of the library. One-dimensional rules start from a random row or, for any other pattern, a single cell")
                 .value_name("initial_state")
+                .possible_values(["random"].into_iter().chain(Pattern::names()))
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("colors")
+                .short('c')
+                .long("colors")
+                .help("Color scheme options: classic, age, trails, age-trails")
+                .value_name("colors")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .help("TOML file of window and display settings, game_of_life.toml by default if it exists. Command line options override its settings")
+                .value_name("config")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("window_width")
+                .long("window-width")
+                .help("Window width in pixels [default: 1100]")
+                .value_name("window_width")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("window_height")
+                .long("window-height")
+                .help("Window height in pixels [default: 1100]")
+                .value_name("window_height")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("fullscreen")
+                .long("fullscreen")
+                .help("Fill the display instead of opening a window")
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::new("fps")
+                .long("fps")
+                .help("Frames drawn per second [default: 30]")
+                .value_name("fps")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("generation_rate")
+                .long("generation-rate")
+                .help("Generations computed per second while running [default: 30]")
+                .value_name("generation_rate")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("max_speed")
+                .long("max-speed")
+                .help("Compute generations as fast as possible instead of at the generation rate, toggled with M")
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::new("grid")
+                .long("grid")
+                .help("Draw the outline of every cell")
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::new("rule")
+                .short('r')
+                .long("rule")
+                .help("Rule in B/S notation (or Immigration, QuadLife and WireWorld), one-dimensional rules such as W110 or T20R2 (totalistic, radius 2), Margolus block rules (Critters, Tron, BBM, or a table such as M15,1,2,3,4,5,6,7,8,9,10,11,12,13,14,0), with a V or H suffix for von Neumann or hexagonal neighbourhoods, in Hensel notation such as B2-a/S12, or in Larger than Life notation such as R5,C0,M1,S34..58,B34..45,NM")
+                .value_name("rule")
+                .takes_value(true)
+                .required(false)
+                .default_value("B3/S23"),
+        )
+        .arg(
+            Arg::new("rule_file")
+                .long("rule-file")
+                .help("Golly .rule file with a @TABLE section, used instead of --rule")
+                .value_name("rule_file")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("neighbourhood")
+                .short('n')
+                .long("neighbourhood")
+                .help("Neighbourhood options: moore, von-neumann, hexagonal, or a mask such as 010/101/010")
+                .value_name("neighbourhood")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("backend")
+                .long("backend")
+                .help("Simulation backend options: dense (every rule and scheme), sparse (life-like rules without B0 under the synchronous scheme, faster for large grids with few live cells)")
+                .value_name("backend")
+                .takes_value(true)
+                .required(false)
+                .default_value("dense"),
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .help("Threads the grid is updated on, every available core by default. Small grids are always updated on one")
+                .value_name("threads")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("scheme")
+                .long("scheme")
+                .help("Update scheme options: synchronous, random-order, async:<alpha> (each cell updated with probability alpha), probabilistic:<birth>,<survival> (births and survivals happening with these probabilities)")
+                .value_name("scheme")
+                .takes_value(true)
+                .required(false)
+                .default_value("synchronous"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .help("Seed of the random update schemes, random by default")
+                .value_name("seed")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("history_mb")
+                .long("history-mb")
+                .help("Memory kept for stepping back through past generations, in MiB")
+                .value_name("history_mb")
+                .takes_value(true)
+                .required(false)
+                .default_value("256"),
+        )
+        .arg(
+            Arg::new("load")
+                .long("load")
+                .help("Snapshot to resume, whose size, rule and update scheme replace the other options")
+                .value_name("load")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("snapshot_file")
+                .long("snapshot-file")
+                .help("File snapshots are saved to with F5 and loaded from with F9")
+                .value_name("snapshot_file")
                 .takes_value(true)
                 .required(false)
-                .default_value("random"),
+                .default_value("snapshot.gol"),
         )
-        .get_matches();
+        .subcommand(bench::command())
+}
+
+/// Configuration from the command line and the settings file, with the grid of the snapshot
+/// to resume if one was given
+fn configure(matches: &ArgMatches) -> Result<(Config, Option<Grid>)> {
+    // Settings from the file, overridden by the command line
+    let mut settings = match matches.value_of("config") {
+        Some(path) => Settings::load(Path::new(path))?,
+        None if Path::new(DEFAULT_SETTINGS_FILE).exists() => {
+            Settings::load(Path::new(DEFAULT_SETTINGS_FILE))?
+        }
+        None => Settings::default(),
+    };
+    if let Some(width) = matches.value_of("window_width") {
+        settings.window_width = Some(parse_flag("--window-width", width, "a size in pixels")?);
+    }
+    if let Some(height) = matches.value_of("window_height") {
+        settings.window_height = Some(parse_flag("--window-height", height, "a size in pixels")?);
+    }
+    if matches.is_present("fullscreen") {
+        settings.fullscreen = Some(true);
+    }
+    if let Some(fps) = matches.value_of("fps") {
+        settings.fps = Some(parse_flag("--fps", fps, "a whole number of frames")?);
+    }
+    if let Some(rate) = matches.value_of("generation_rate") {
+        settings.generation_rate = Some(parse_flag(
+            "--generation-rate",
+            rate,
+            "a whole number of generations",
+        )?);
+    }
+    if matches.is_present("max_speed") {
+        settings.max_speed = Some(true);
+    }
+    if matches.is_present("grid") {
+        settings.grid = Some(true);
+    }
+    if let Some(colors) = matches.value_of("colors") {
+        settings.colors = Some(colors.to_string());
+    }
+    if let Some(initial_state) = matches.value_of("initial_state") {
+        settings.initial_state = Some(initial_state.to_string());
+    }
+    settings.validate()?;
 
     // Get Configurations
-    let grid_width = matches.value_of("width").unwrap().parse::<usize>().unwrap();
-    let grid_height = matches
-        .value_of("height")
+    let loaded = match matches.value_of("load") {
+        Some(path) => Some(snapshot::load(Path::new(path))?),
+        None => None,
+    };
+    let mut grid_width = parse_flag::<usize>(
+        "--width",
+        matches.value_of("width").unwrap(),
+        "a number of cells",
+    )?;
+    let mut grid_height = parse_flag::<usize>(
+        "--height",
+        matches.value_of("height").unwrap(),
+        "a number of cells",
+    )?;
+    for (name, size) in [("--width", grid_width), ("--height", grid_height)] {
+        if size == 0 {
+            return Err(Error::InvalidArgument {
+                name: name.to_string(),
+                value: size.to_string(),
+                expected: "at least 1 cell".to_string(),
+            });
+        }
+    }
+    let initial_state = settings.initial_state.as_deref().unwrap_or("random");
+    let color_scheme = settings
+        .colors
+        .as_deref()
+        .unwrap_or("classic")
+        .parse::<ColorScheme>()?;
+    let mut rule = if let Some(path) = matches.value_of("rule_file") {
+        let contents = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: PathBuf::from(path),
+            source,
+        })?;
+        Rule::Table(contents.parse::<RuleTable>()?)
+    } else {
+        let rulestring = matches.value_of("rule").unwrap();
+        // Reported as a bad argument, naming the option, like the other flags
+        let invalid_rule = |err: String| Error::InvalidArgument {
+            name: "--rule".to_string(),
+            value: rulestring.to_string(),
+            expected: format!("a rulestring ({})", err),
+        };
+        match matches.value_of("neighbourhood") {
+            Some(neighbourhood) => Rule::Life(
+                LifeRule::parse_for(
+                    rulestring,
+                    neighbourhood.parse::<Neighbourhood>().map_err(|err| {
+                        Error::InvalidArgument {
+                            name: "--neighbourhood".to_string(),
+                            value: neighbourhood.to_string(),
+                            expected: format!("a neighbourhood ({})", err),
+                        }
+                    })?,
+                )
+                .map_err(invalid_rule)?,
+            ),
+            None => rulestring.parse::<Rule>().map_err(invalid_rule)?,
+        }
+    };
+    let mut scheme = matches
+        .value_of("scheme")
         .unwrap()
-        .parse::<usize>()
-        .unwrap();
-    let initial_state = matches.value_of("initial_state").unwrap();
-    let screen_size = (1100.0, 1100.0);
-    let fps = 30;
+        .parse::<UpdateScheme>()?;
+    let mut seed = match matches.value_of("seed") {
+        Some(seed) => parse_flag("--seed", seed, "a whole number")?,
+        None => rand::random::<u64>(),
+    };
+    if let Some(grid) = &loaded {
+        grid_width = grid.width();
+        grid_height = grid.height();
+        rule = grid.rule().clone();
+        scheme = grid.scheme();
+        seed = grid.seed();
+    }
+    let history_mb = parse_flag::<usize>(
+        "--history-mb",
+        matches.value_of("history_mb").unwrap(),
+        "a whole number of MiB",
+    )?;
+    let history_cap = history_mb
+        .checked_mul(1 << 20)
+        .ok_or_else(|| Error::InvalidArgument {
+            name: "--history-mb".to_string(),
+            value: history_mb.to_string(),
+            expected: format!("at most {} MiB", usize::MAX >> 20),
+        })?;
+    let threads = match matches.value_of("threads") {
+        Some(threads) => match parse_flag::<usize>("--threads", threads, "at least 1 thread")? {
+            0 => {
+                return Err(Error::InvalidArgument {
+                    name: "--threads".to_string(),
+                    value: threads.to_string(),
+                    expected: "at least 1 thread".to_string(),
+                })
+            }
+            threads => Some(threads),
+        },
+        None => None,
+    };
+    let screen_size = (
+        settings.window_width.unwrap_or(1100.0),
+        settings.window_height.unwrap_or(1100.0),
+    );
     // Set configuration
     let config: Config = Config {
         grid_width,
         grid_height,
-        cell_size: screen_size.0 / grid_width as f32,
+        cell_size: layout_cell_size(screen_size.0, grid_width, grid_height, &rule),
         screen_size,
-        fps,
+        fullscreen: settings.fullscreen.unwrap_or(false),
+        fps: settings.fps.unwrap_or(30),
+        generation_rate: settings.generation_rate.unwrap_or(30),
+        max_speed: settings.max_speed.unwrap_or(false),
+        grid_overlay: settings.grid.unwrap_or(false),
         initial_state: initial_state.to_string(),
+        color_scheme,
+        rule,
+        scheme,
+        seed,
+        history_cap,
+        snapshot_file: PathBuf::from(matches.value_of("snapshot_file").unwrap()),
+        backend: matches.value_of("backend").unwrap().parse::<Backend>()?,
+        threads,
     };
+    Ok((config, loaded))
+}
 
-    // Setup ggez stuff
-    let cb = ContextBuilder::new("Game of life", "J. Rene H.S.")
-        .window_mode(ggez::conf::WindowMode::default().dimensions(screen_size.0, screen_size.1));
-    let (mut ctx, event_loop) = cb.build()?;
-    graphics::set_window_title(&ctx, "Game of life");
-    // Setup game state -> game loop
-    let state = MainState::new(&mut ctx, config);
+/// Value of a command line option, with an error naming the option and what it takes if it
+/// can't be parsed
+fn parse_flag<T: FromStr>(name: &str, value: &str, expected: &str) -> Result<T> {
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| Error::InvalidArgument {
+            name: name.to_string(),
+            value: value.to_string(),
+            expected: expected.to_string(),
+        })
+}
 
-    event::run(ctx, event_loop, state);
+/// Reports an error that keeps the game from starting and exits
+fn exit_with(err: Error) -> ! {
+    eprintln!("error: {}", err);
+    std::process::exit(err.exit_code())
 }
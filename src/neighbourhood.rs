@@ -0,0 +1,124 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Cells around a cell whose states are counted by the rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The 8 surrounding cells
+    Moore,
+    /// The 4 orthogonally adjacent cells
+    VonNeumann,
+    /// The 6 adjacent cells of a hexagonal layout, stored with every row skewed
+    /// half a cell to the left of the one above it
+    Hexagonal,
+    /// Arbitrary offsets relative to the cell
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Neighbourhood {
+    /// Offsets (x, y) of the neighbours relative to the cell
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            Neighbourhood::Moore => vec![
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+            Neighbourhood::VonNeumann => vec![(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Neighbourhood::Hexagonal => vec![(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)],
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+    /// Number of neighbours, the highest count a rule can see
+    pub fn size(&self) -> usize {
+        self.offsets().len()
+    }
+    pub fn is_hexagonal(&self) -> bool {
+        *self == Neighbourhood::Hexagonal
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    /// Name of the neighbourhood, or the smallest mask holding a custom one
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let offsets = match self {
+            Neighbourhood::Moore => return write!(f, "moore"),
+            Neighbourhood::VonNeumann => return write!(f, "von-neumann"),
+            Neighbourhood::Hexagonal => return write!(f, "hexagonal"),
+            Neighbourhood::Custom(offsets) => offsets,
+        };
+        let range_x = offsets
+            .iter()
+            .map(|offset| offset.0.abs())
+            .max()
+            .unwrap_or(0);
+        let range_y = offsets
+            .iter()
+            .map(|offset| offset.1.abs())
+            .max()
+            .unwrap_or(0);
+        let rows = (-range_y..=range_y)
+            .map(|y| {
+                (-range_x..=range_x)
+                    .map(|x| match offsets.contains(&(x, y)) {
+                        true => '1',
+                        false => '0',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", rows.join("/"))
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    /// Parses `moore`, `von-neumann`, `hexagonal` or a custom mask.
+    ///
+    /// A custom mask is a rectangle of odd width and height centred on the cell, with its rows
+    /// separated by `/`, where `1` marks a neighbour and `0` or `.` does not, e.g. `010/101/010`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "moore" => return Ok(Neighbourhood::Moore),
+            "von-neumann" => return Ok(Neighbourhood::VonNeumann),
+            "hex" | "hexagonal" => return Ok(Neighbourhood::Hexagonal),
+            _ => {}
+        }
+        if s.chars().any(|c| !matches!(c, '0' | '1' | '.' | '/')) {
+            return Err(format!("unknown neighbourhood '{}'", s));
+        }
+        let rows = s.split('/').collect::<Vec<&str>>();
+        let width = rows[0].len();
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(format!(
+                "rows of neighbourhood mask '{}' differ in length",
+                s
+            ));
+        }
+        if width % 2 == 0 || rows.len() % 2 == 0 {
+            return Err(format!(
+                "neighbourhood mask '{}' must have an odd width and height",
+                s
+            ));
+        }
+        let (centre_x, centre_y) = ((width / 2) as isize, (rows.len() / 2) as isize);
+        let mut offsets = vec![];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '1' {
+                    offsets.push((x as isize - centre_x, y as isize - centre_y));
+                }
+            }
+        }
+        if offsets.is_empty() {
+            return Err(format!("neighbourhood mask '{}' has no neighbours", s));
+        }
+        Ok(Neighbourhood::Custom(offsets))
+    }
+}
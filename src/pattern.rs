@@ -0,0 +1,311 @@
+use crate::engine::LifeEngine;
+use crate::rule::Rule;
+use crate::structs::{pattern_named, PATTERNS, WIREWORLD_CLOCK};
+use crate::types::Point;
+use std::str::FromStr;
+
+/// Longest line written in RLE bodies
+const RLE_LINE_LENGTH: usize = 70;
+/// Largest width and height of a parsed RLE pattern, whether declared in its header or not
+const RLE_MAX_SIDE: usize = 1 << 16;
+/// Most live cells a parsed RLE pattern may have, so pasted text can't exhaust memory
+const RLE_MAX_CELLS: usize = 1 << 22;
+
+/// Cells of a rectangular region, independent of where it sits on a grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    /// Live cells relative to the top-left corner, with their state
+    cells: Vec<(Point, u8)>,
+}
+
+impl Pattern {
+    pub fn new(width: usize, height: usize, cells: Vec<(Point, u8)>) -> Self {
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+    /// Pattern of the cells in the rectangle of the universe from `corner` to `opposite`, both
+    /// included
+    pub fn from_engine(engine: &dyn LifeEngine, corner: Point, opposite: Point) -> Self {
+        let (min_x, max_x) = (corner.x.min(opposite.x), corner.x.max(opposite.x));
+        let (min_y, max_y) = (corner.y.min(opposite.y), corner.y.max(opposite.y));
+        let max_x = max_x.min(engine.width() - 1);
+        let max_y = max_y.min(engine.height() - 1);
+        let mut cells = vec![];
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let state = engine
+                    .get_cell(Point { x, y })
+                    .map_or(0, |cell| cell.state());
+                if state != 0 {
+                    cells.push((Point::from((x - min_x, y - min_y)), state));
+                }
+            }
+        }
+        Self::new(max_x + 1 - min_x, max_y + 1 - min_y, cells)
+    }
+    /// Pattern of the built-in library called `name`, one of the names in `structs::PATTERNS`
+    /// or `wireworld-clock`, with its cells where they are placed when a grid starts from it
+    pub fn named(name: &str) -> Option<Self> {
+        let cells = match name {
+            "wireworld-clock" => WIREWORLD_CLOCK
+                .iter()
+                .map(|&(x, y, state)| ((x, y).into(), state))
+                .collect::<Vec<(Point, u8)>>(),
+            name => pattern_named(name)?
+                .iter()
+                .map(|&pos| (pos.into(), 1))
+                .collect::<Vec<(Point, u8)>>(),
+        };
+        let width = cells.iter().map(|(pos, _)| pos.x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|(pos, _)| pos.y + 1).max().unwrap_or(0);
+        Some(Self::new(width, height, cells))
+    }
+    /// Name of every pattern `Pattern::named` knows
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        PATTERNS
+            .iter()
+            .map(|&(name, _)| name)
+            .chain(["wireworld-clock"])
+    }
+    /// Same pattern without the empty rows and columns around its cells
+    pub fn trimmed(&self) -> Self {
+        let min_x = self.cells.iter().map(|(pos, _)| pos.x).min().unwrap_or(0);
+        let min_y = self.cells.iter().map(|(pos, _)| pos.y).min().unwrap_or(0);
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(pos, state)| (Point::from((pos.x - min_x, pos.y - min_y)), state))
+            .collect::<Vec<(Point, u8)>>();
+        let width = cells.iter().map(|(pos, _)| pos.x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|(pos, _)| pos.y + 1).max().unwrap_or(0);
+        Self::new(width, height, cells)
+    }
+    /// Live cells relative to the top-left corner, with their state
+    pub fn cells(&self) -> &[(Point, u8)] {
+        &self.cells
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Same pattern turned a quarter turn clockwise
+    pub fn rotate_clockwise(&self) -> Self {
+        self.map(self.height, self.width, |pos| {
+            (self.height - 1 - pos.y, pos.x).into()
+        })
+    }
+    /// Same pattern mirrored left to right
+    pub fn flip_horizontal(&self) -> Self {
+        self.map(self.width, self.height, |pos| {
+            (self.width - 1 - pos.x, pos.y).into()
+        })
+    }
+    /// Same pattern mirrored top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        self.map(self.width, self.height, |pos| {
+            (pos.x, self.height - 1 - pos.y).into()
+        })
+    }
+    /// Cells of the pattern with its top-left corner at `origin`
+    pub fn placed_at(&self, origin: Point) -> Vec<(Point, u8)> {
+        self.cells
+            .iter()
+            .map(|&(pos, state)| ((origin.x + pos.x, origin.y + pos.y).into(), state))
+            .collect()
+    }
+    /// Run length encoded text of the pattern, as read by most Life programs
+    pub fn to_rle(&self, rule: &Rule) -> String {
+        let multistate = self.cells.iter().any(|&(_, state)| state > 1);
+        let mut rows = vec![vec![0; self.width]; self.height];
+        for &(pos, state) in self.cells.iter() {
+            rows[pos.y][pos.x] = state;
+        }
+
+        // Runs of (count, tag), leaving out dead cells at the end of rows
+        let mut runs: Vec<(usize, String)> = vec![];
+        let mut push = |count: usize, tag: String| match runs.last_mut() {
+            Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+            _ => runs.push((count, tag)),
+        };
+        for (y, row) in rows.iter().enumerate() {
+            let end = row
+                .iter()
+                .rposition(|&state| state != 0)
+                .map_or(0, |x| x + 1);
+            for &state in row[..end].iter() {
+                push(1, rle_tag(state, multistate));
+            }
+            if y + 1 < self.height {
+                push(1, "$".to_string());
+            }
+        }
+        // Empty rows at the end are implied
+        while runs.last().is_some_and(|(_, tag)| tag == "$") {
+            runs.pop();
+        }
+
+        let mut rle = format!("x = {}, y = {}, rule = {}\n", self.width, self.height, rule);
+        let mut line = String::new();
+        for (count, tag) in runs {
+            let run = match count {
+                1 => tag,
+                _ => format!("{}{}", count, tag),
+            };
+            if line.len() + run.len() > RLE_LINE_LENGTH {
+                rle.push_str(&line);
+                rle.push('\n');
+                line.clear();
+            }
+            line.push_str(&run);
+        }
+        rle.push_str(&line);
+        rle.push_str("!\n");
+        rle
+    }
+    fn map(&self, width: usize, height: usize, transform: impl Fn(Point) -> Point) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(pos, state)| (transform(pos), state))
+            .collect();
+        Self::new(width, height, cells)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    /// Parses a run length encoded pattern. The rule in its header is ignored, and dimensions
+    /// missing from it are taken from the cells. Runs going past the dimensions of the header,
+    /// or past `RLE_MAX_SIDE` without one, are rejected
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut size = (None, None);
+        let mut body = String::new();
+        for line in s.lines().map(|line| line.trim()) {
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            if line.starts_with('x') && body.is_empty() {
+                for setting in line.split(',') {
+                    let (key, value) = setting.split_once('=').unwrap_or((setting, ""));
+                    let value = value.trim().parse::<usize>().ok();
+                    match key.trim() {
+                        "x" => size.0 = value,
+                        "y" => size.1 = value,
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+            body.push_str(line);
+        }
+        if size.0.unwrap_or(0) > RLE_MAX_SIDE || size.1.unwrap_or(0) > RLE_MAX_SIDE {
+            return Err(format!(
+                "RLE pattern is larger than {} cells a side",
+                RLE_MAX_SIDE
+            ));
+        }
+        let (max_x, max_y) = (
+            size.0.unwrap_or(RLE_MAX_SIDE),
+            size.1.unwrap_or(RLE_MAX_SIDE),
+        );
+        let outside = || {
+            format!(
+                "RLE pattern goes past its size of {}x{} cells",
+                max_x, max_y
+            )
+        };
+
+        let mut cells = vec![];
+        let (mut x, mut y): (usize, usize) = (0, 0);
+        let mut count: Option<usize> = None;
+        let mut prefix = None;
+        for c in body.chars().filter(|c| !c.is_whitespace()) {
+            if let Some(digit) = c.to_digit(10) {
+                count = Some(
+                    count
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit as usize))
+                        .ok_or_else(outside)?,
+                );
+                continue;
+            }
+            // States above 24 are written as a letter from `p` followed by one from `A`
+            if ('p'..='y').contains(&c) {
+                prefix = Some((c as u8 - b'p') as usize + 1);
+                continue;
+            }
+            let run = count.take().unwrap_or(1);
+            match c {
+                'b' | '.' => {
+                    x = x
+                        .checked_add(run)
+                        .filter(|&x| x <= max_x)
+                        .ok_or_else(outside)?
+                }
+                '$' => {
+                    y = y
+                        .checked_add(run)
+                        .filter(|&y| y <= max_y)
+                        .ok_or_else(outside)?;
+                    x = 0;
+                }
+                '!' => break,
+                'o' | 'A'..='X' => {
+                    let state = match c {
+                        'o' => 1,
+                        _ => prefix.take().unwrap_or(0) * 24 + (c as u8 - b'A') as usize + 1,
+                    };
+                    let state = u8::try_from(state)
+                        .map_err(|_| format!("state of '{}' is too large", c))?;
+                    if y >= max_y || x.checked_add(run).is_none_or(|end| end > max_x) {
+                        return Err(outside());
+                    }
+                    if cells.len() + run > RLE_MAX_CELLS {
+                        return Err(format!(
+                            "RLE pattern has more than {} live cells",
+                            RLE_MAX_CELLS
+                        ));
+                    }
+                    for _ in 0..run {
+                        cells.push((Point { x, y }, state));
+                        x += 1;
+                    }
+                }
+                _ => return Err(format!("unexpected '{}' in RLE pattern", c)),
+            }
+        }
+        let width = cells.iter().map(|(pos, _)| pos.x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|(pos, _)| pos.y + 1).max().unwrap_or(0);
+        Ok(Self::new(
+            size.0.unwrap_or(width).max(width),
+            size.1.unwrap_or(height).max(height),
+            cells,
+        ))
+    }
+}
+
+/// RLE tag of a state: `b` and `o` for two state patterns, `.` and letters otherwise
+fn rle_tag(state: u8, multistate: bool) -> String {
+    match (state, multistate) {
+        (0, false) => "b".to_string(),
+        (_, false) => "o".to_string(),
+        (0, true) => ".".to_string(),
+        (state, true) => {
+            let index = state as usize - 1;
+            let letter = (b'A' + (index % 24) as u8) as char;
+            match index / 24 {
+                0 => letter.to_string(),
+                prefix => format!("{}{}", (b'p' + prefix as u8 - 1) as char, letter),
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use crate::elementary::{is_elementary_notation, ElementaryRule};
+use crate::error::Error;
+use crate::isotropic::{is_hensel_notation, IsotropicRule};
+use crate::ltl::LtlRule;
+use crate::margolus::{is_margolus_notation, MargolusRule};
+use crate::neighbourhood::Neighbourhood;
+use crate::table::RuleTable;
+use std::fmt;
+use std::str::FromStr;
+
+/// Largest number of colours of a multicolour variant
+pub const MAX_COLOURS: usize = 4;
+
+/// Rule deciding the next state of every cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// Life-like rule in B/S notation
+    Life(LifeRule),
+    /// Larger than Life rule, with neighbourhoods of extended range
+    LargerThanLife(LtlRule),
+    /// Isotropic non-totalistic rule in Hensel notation
+    Isotropic(IsotropicRule),
+    /// Multi-state rule given by a transition table
+    Table(RuleTable),
+    /// One-dimensional rule, each generation being a new row of the grid
+    Elementary(ElementaryRule),
+    /// Partitioning rule replacing 2x2 blocks, whose alignment alternates between generations
+    Margolus(MargolusRule),
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23
+    pub fn conway() -> Self {
+        Rule::Life(LifeRule::conway())
+    }
+    pub fn is_one_dimensional(&self) -> bool {
+        matches!(self, Rule::Elementary(_))
+    }
+    /// Whether cells should be laid out as hexagons
+    pub fn is_hexagonal(&self) -> bool {
+        match self {
+            Rule::Life(rule) => rule.neighbourhood().is_hexagonal(),
+            Rule::LargerThanLife(_)
+            | Rule::Isotropic(_)
+            | Rule::Table(_)
+            | Rule::Elementary(_)
+            | Rule::Margolus(_) => false,
+        }
+    }
+    /// Number of colours live cells can have, for rule tables the number of non-empty states
+    pub fn colours(&self) -> u8 {
+        match self {
+            Rule::Life(rule) => rule.colours(),
+            Rule::LargerThanLife(_)
+            | Rule::Isotropic(_)
+            | Rule::Elementary(_)
+            | Rule::Margolus(_) => 1,
+            Rule::Table(table) => table.states() - 1,
+        }
+    }
+    /// Fails unless `state` is one cells can be in under the rule: 0 for empty cells, or one
+    /// of the `colours` of live cells
+    pub fn check_state(&self, state: u8) -> crate::error::Result<()> {
+        if state <= self.colours() {
+            return Ok(());
+        }
+        Err(Error::InvalidArgument {
+            name: "state".to_string(),
+            value: state.to_string(),
+            expected: format!("a state of {}, from 0 to {}", self, self.colours()),
+        })
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl fmt::Display for Rule {
+    /// Rulestring the rule parses back from. Rule tables are shown by name, and custom
+    /// neighbourhoods are left out
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Life(rule) => rule.fmt(f),
+            Rule::LargerThanLife(rule) => rule.fmt(f),
+            Rule::Isotropic(rule) => rule.fmt(f),
+            Rule::Table(table) => write!(f, "{}", table.name()),
+            Rule::Elementary(rule) => rule.fmt(f),
+            Rule::Margolus(rule) => rule.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses either Larger than Life notation, e.g. `R5,C0,M1,S34..58,B34..45,NM`, B/S
+    /// notation, Hensel notation, e.g. `B2-a/S12`, `WireWorld`, one-dimensional rules such as
+    /// `W110`, or Margolus block rules such as `Critters`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().to_lowercase() == "wireworld" {
+            Ok(Rule::Table(RuleTable::wireworld()))
+        } else if is_elementary_notation(s) {
+            Ok(Rule::Elementary(s.parse::<ElementaryRule>()?))
+        } else if is_margolus_notation(s) {
+            Ok(Rule::Margolus(s.parse::<MargolusRule>()?))
+        } else if s.trim().to_uppercase().starts_with('R') {
+            Ok(Rule::LargerThanLife(s.parse::<LtlRule>()?))
+        } else if is_hensel_notation(s) {
+            Ok(Rule::Isotropic(s.parse::<IsotropicRule>()?))
+        } else {
+            Ok(Rule::Life(s.parse::<LifeRule>()?))
+        }
+    }
+}
+
+/// Outer totalistic rule: the next state only depends on the current state and the number of
+/// live neighbours
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifeRule {
+    /// Indexed by the number of live neighbours
+    birth: Vec<bool>,
+    /// Indexed by the number of live neighbours
+    survival: Vec<bool>,
+    neighbourhood: Neighbourhood,
+    /// Number of colours, newborn cells take the majority colour of their neighbours
+    colours: u8,
+}
+
+impl LifeRule {
+    pub fn new(birth: &[usize], survival: &[usize], neighbourhood: Neighbourhood) -> Self {
+        let size = neighbourhood.size();
+        let mut rule = Self {
+            birth: vec![false; size + 1],
+            survival: vec![false; size + 1],
+            neighbourhood,
+            colours: 1,
+        };
+        for &count in birth.iter().filter(|&&count| count <= size) {
+            rule.birth[count] = true;
+        }
+        for &count in survival.iter().filter(|&&count| count <= size) {
+            rule.survival[count] = true;
+        }
+        rule
+    }
+    /// Conway's Game of Life, B3/S23
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3], Neighbourhood::Moore)
+    }
+    /// Immigration, Conway's Game of Life with 2 colours
+    pub fn immigration() -> Self {
+        Self::conway().with_colours(2)
+    }
+    /// QuadLife, Conway's Game of Life with 4 colours
+    pub fn quadlife() -> Self {
+        Self::conway().with_colours(4)
+    }
+    /// Same rule where live cells have one of `colours` colours, up to `MAX_COLOURS`
+    pub fn with_colours(mut self, colours: u8) -> Self {
+        self.colours = colours.clamp(1, MAX_COLOURS as u8);
+        self
+    }
+    pub fn neighbourhood(&self) -> &Neighbourhood {
+        &self.neighbourhood
+    }
+    pub fn colours(&self) -> u8 {
+        self.colours
+    }
+    pub fn birth_counts(&self) -> Vec<usize> {
+        counts(&self.birth)
+    }
+    pub fn survival_counts(&self) -> Vec<usize> {
+        counts(&self.survival)
+    }
+    pub fn next_state(&self, alive: bool, num_neighbour_alive: usize) -> bool {
+        if alive {
+            self.survival[num_neighbour_alive]
+        } else {
+            self.birth[num_neighbour_alive]
+        }
+    }
+    /// Colour of a newborn cell given how many of its live neighbours have each colour.
+    ///
+    /// The majority colour wins. When three different colours tie, as happens for QuadLife
+    /// births, the cell takes the remaining colour. Other ties go to the lowest colour.
+    pub fn newborn_colour(&self, colour_counts: &[usize; MAX_COLOURS + 1]) -> u8 {
+        let colours = &colour_counts[1..=self.colours as usize];
+        let majority = colours.iter().copied().max().unwrap_or(0);
+        let tied = colours.iter().filter(|&&count| count == majority).count();
+        if self.colours == 4 && majority == 1 && tied == 3 {
+            if let Some(missing) = colours.iter().position(|&count| count == 0) {
+                return missing as u8 + 1;
+            }
+        }
+        colours
+            .iter()
+            .position(|&count| count == majority)
+            .map_or(1, |colour| colour as u8 + 1)
+    }
+    /// Parses the counts of a rulestring for the given neighbourhood, ignoring any `V` or `H`
+    /// suffix
+    pub fn parse_for(s: &str, neighbourhood: Neighbourhood) -> Result<Self, String> {
+        let rulestring = s
+            .trim()
+            .to_uppercase()
+            .trim_end_matches(['V', 'H'])
+            .to_string();
+        let parts = rulestring.split('/').collect::<Vec<&str>>();
+        if parts.len() != 2 {
+            return Err(format!("rule '{}' is not in B/S notation", s));
+        }
+        let (birth, survival) = match (parts[0].strip_prefix('B'), parts[1].strip_prefix('S')) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            _ => match (parts[0].strip_prefix('S'), parts[1].strip_prefix('B')) {
+                (Some(survival), Some(birth)) => (birth, survival),
+                // Survival/birth without letters
+                _ => (parts[1], parts[0]),
+            },
+        };
+        let size = neighbourhood.size();
+        let birth = parse_counts(birth, size)?;
+        let survival = parse_counts(survival, size)?;
+        Ok(Self::new(&birth, &survival, neighbourhood))
+    }
+}
+
+impl FromStr for LifeRule {
+    type Err = String;
+
+    /// Parses a rulestring such as `B3/S23`, or the older `23/3` survival/birth form.
+    ///
+    /// A trailing `V` selects the von Neumann neighbourhood and `H` the hexagonal one. Counts are
+    /// single digits unless they are separated by commas, e.g. `B3,10/S2,3`. The multicolour
+    /// variants are named `Immigration` and `QuadLife`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "immigration" => return Ok(Self::immigration()),
+            "quadlife" => return Ok(Self::quadlife()),
+            _ => {}
+        }
+        let neighbourhood = match s.trim().chars().last() {
+            Some('V' | 'v') => Neighbourhood::VonNeumann,
+            Some('H' | 'h') => Neighbourhood::Hexagonal,
+            _ => Neighbourhood::Moore,
+        };
+        Self::parse_for(s, neighbourhood)
+    }
+}
+
+impl fmt::Display for LifeRule {
+    /// B/S notation with a `V` or `H` suffix, or the name of a multicolour variant
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == Self::immigration() {
+            return write!(f, "Immigration");
+        }
+        if *self == Self::quadlife() {
+            return write!(f, "QuadLife");
+        }
+        let suffix = match self.neighbourhood {
+            Neighbourhood::VonNeumann => "V",
+            Neighbourhood::Hexagonal => "H",
+            Neighbourhood::Moore | Neighbourhood::Custom(_) => "",
+        };
+        write!(
+            f,
+            "B{}/S{}{}",
+            format_counts(&self.birth_counts()),
+            format_counts(&self.survival_counts()),
+            suffix
+        )
+    }
+}
+
+/// Parses the neighbour counts of one half of a rulestring
+fn parse_counts(s: &str, max: usize) -> Result<Vec<usize>, String> {
+    let counts = if s.contains(',') {
+        s.split(',')
+            .filter(|count| !count.is_empty())
+            .map(|count| count.parse::<usize>().ok())
+            .collect::<Option<Vec<usize>>>()
+    } else {
+        s.chars()
+            .map(|c| c.to_digit(10).map(|count| count as usize))
+            .collect::<Option<Vec<usize>>>()
+    };
+    let counts = counts.ok_or_else(|| format!("invalid neighbour counts '{}'", s))?;
+    match counts.iter().find(|&&count| count > max) {
+        Some(count) => Err(format!(
+            "neighbour count {} is larger than the neighbourhood ({})",
+            count, max
+        )),
+        None => Ok(counts),
+    }
+}
+
+/// Writes counts as digits, or separated by commas when some have two digits. A single count
+/// gets a trailing comma so it isn't read as several digits
+fn format_counts(counts: &[usize]) -> String {
+    if counts.iter().all(|&count| count < 10) {
+        return counts.iter().map(|count| count.to_string()).collect();
+    }
+    let counts = counts
+        .iter()
+        .map(|count| count.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    if counts.contains(',') {
+        counts
+    } else {
+        counts + ","
+    }
+}
+
+/// Neighbour counts enabled in a lookup vector
+fn counts(enabled: &[bool]) -> Vec<usize> {
+    enabled
+        .iter()
+        .enumerate()
+        .filter(|(_, &enabled)| enabled)
+        .map(|(count, _)| count)
+        .collect()
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Order and reliability with which cells are updated in each generation
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum UpdateScheme {
+    /// Every cell is updated at once from the previous generation
+    #[default]
+    Synchronous,
+    /// Cells are updated one at a time in a new random order every generation, each seeing the
+    /// cells already updated
+    RandomOrder,
+    /// Each cell is updated with probability `alpha` and otherwise keeps its state
+    Asynchronous { alpha: f64 },
+    /// Births happen with probability `birth` and live cells survive with probability
+    /// `survival`, when the rule allows it
+    Probabilistic { birth: f64, survival: f64 },
+}
+
+impl UpdateScheme {
+    pub fn is_synchronous(&self) -> bool {
+        matches!(self, UpdateScheme::Synchronous)
+    }
+}
+
+impl fmt::Display for UpdateScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UpdateScheme::Synchronous => write!(f, "synchronous"),
+            UpdateScheme::RandomOrder => write!(f, "random-order"),
+            UpdateScheme::Asynchronous { alpha } => write!(f, "async:{}", alpha),
+            UpdateScheme::Probabilistic { birth, survival } => {
+                write!(f, "probabilistic:{},{}", birth, survival)
+            }
+        }
+    }
+}
+
+impl FromStr for UpdateScheme {
+    type Err = String;
+
+    /// Parses `synchronous`, `random-order`, `async:<alpha>` such as `async:0.5`, or
+    /// `probabilistic:<birth>,<survival>` such as `probabilistic:0.9,0.99`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let scheme = s.trim().to_lowercase();
+        let (name, args) = scheme.split_once(':').unwrap_or((&scheme, ""));
+        let probabilities = args
+            .split(',')
+            .map(|p| {
+                p.trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|p| (0.0..=1.0).contains(p))
+            })
+            .collect::<Option<Vec<f64>>>();
+        match (name, probabilities.as_deref()) {
+            ("synchronous", _) if args.is_empty() => Ok(UpdateScheme::Synchronous),
+            ("random-order", _) if args.is_empty() => Ok(UpdateScheme::RandomOrder),
+            ("async", Some(&[alpha])) => Ok(UpdateScheme::Asynchronous { alpha }),
+            ("probabilistic", Some(&[birth, survival])) => {
+                Ok(UpdateScheme::Probabilistic { birth, survival })
+            }
+            ("async", _) => Err(format!(
+                "scheme '{}' needs a probability between 0 and 1",
+                s
+            )),
+            ("probabilistic", _) => Err(format!(
+                "scheme '{}' needs birth and survival probabilities between 0 and 1",
+                s
+            )),
+            _ => Err(format!("unknown update scheme '{}'", s)),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use game_of_life::error::{Error, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// File settings are read from when no other is given, if it exists
+pub const DEFAULT_SETTINGS_FILE: &str = "game_of_life.toml";
+
+/// Window and display options, read from a TOML file such as
+///
+/// ```toml
+/// window-width = 800
+/// window-height = 800
+/// fullscreen = false
+/// fps = 60
+/// generation-rate = 10
+/// max-speed = false
+/// grid = true
+/// colors = "age-trails"
+/// initial-state = "glider-gun"
+/// ```
+///
+/// Options left out of the file keep their defaults, and any of them can be overridden from
+/// the command line
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Settings {
+    pub window_width: Option<f32>,
+    pub window_height: Option<f32>,
+    pub fullscreen: Option<bool>,
+    /// Frames drawn per second
+    pub fps: Option<u32>,
+    /// Generations computed per second while running
+    pub generation_rate: Option<u32>,
+    /// Whether generations are computed as fast as possible instead
+    pub max_speed: Option<bool>,
+    /// Whether the outline of every cell is drawn
+    pub grid: Option<bool>,
+    pub colors: Option<String>,
+    pub initial_state: Option<String>,
+}
+
+impl Settings {
+    /// Reads the settings of a TOML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&contents)
+            .map_err(|err| Error::Parse(format!("invalid settings in {}: {}", path.display(), err)))
+    }
+    /// Checks the numbers are in range, so the window can be opened with them: sizes of at
+    /// least a pixel and rates of at least one per second
+    pub fn validate(&self) -> Result<()> {
+        for (name, size) in [
+            ("window width", self.window_width),
+            ("window height", self.window_height),
+        ] {
+            if let Some(size) = size.filter(|size| !size.is_finite() || *size < 1.) {
+                return Err(Error::InvalidArgument {
+                    name: name.to_string(),
+                    value: size.to_string(),
+                    expected: "a size of at least 1 pixel".to_string(),
+                });
+            }
+        }
+        for (name, rate) in [("fps", self.fps), ("generation rate", self.generation_rate)] {
+            if rate == Some(0) {
+                return Err(Error::InvalidArgument {
+                    name: name.to_string(),
+                    value: "0".to_string(),
+                    expected: "a rate of at least 1 per second".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
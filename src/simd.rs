@@ -0,0 +1,63 @@
+//! Wide adders for counting neighbours, picked at runtime from what the CPU supports
+
+/// Adds `addend` to `counts` lane by lane, wrapping on overflow. Only as many lanes as the
+/// shorter of the two are added
+pub fn add(counts: &mut [u8], addend: &[u8]) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") {
+            // Safety: the CPU supports AVX2
+            unsafe { add_avx2(counts, addend) }
+        } else {
+            // Safety: every x86_64 CPU supports SSE2
+            unsafe { add_sse2(counts, addend) }
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    add_scalar(counts, addend)
+}
+
+/// `add` one lane at a time, for CPUs without wide adders and the lanes left over by them
+pub fn add_scalar(counts: &mut [u8], addend: &[u8]) {
+    for (count, &value) in counts.iter_mut().zip(addend) {
+        *count = count.wrapping_add(value);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn add_avx2(counts: &mut [u8], addend: &[u8]) {
+    use std::arch::x86_64::{__m256i, _mm256_add_epi8, _mm256_loadu_si256, _mm256_storeu_si256};
+
+    const LANES: usize = 32;
+    let len = counts.len().min(addend.len());
+    let wide = len - len % LANES;
+    for start in (0..wide).step_by(LANES) {
+        let count = counts.as_mut_ptr().add(start) as *mut __m256i;
+        let value = addend.as_ptr().add(start) as *const __m256i;
+        _mm256_storeu_si256(
+            count,
+            _mm256_add_epi8(_mm256_loadu_si256(count), _mm256_loadu_si256(value)),
+        );
+    }
+    add_scalar(&mut counts[wide..len], &addend[wide..len]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn add_sse2(counts: &mut [u8], addend: &[u8]) {
+    use std::arch::x86_64::{__m128i, _mm_add_epi8, _mm_loadu_si128, _mm_storeu_si128};
+
+    const LANES: usize = 16;
+    let len = counts.len().min(addend.len());
+    let wide = len - len % LANES;
+    for start in (0..wide).step_by(LANES) {
+        let count = counts.as_mut_ptr().add(start) as *mut __m128i;
+        let value = addend.as_ptr().add(start) as *const __m128i;
+        _mm_storeu_si128(
+            count,
+            _mm_add_epi8(_mm_loadu_si128(count), _mm_loadu_si128(value)),
+        );
+    }
+    add_scalar(&mut counts[wide..len], &addend[wide..len]);
+}
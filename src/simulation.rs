@@ -0,0 +1,185 @@
+use crate::cell::Cell;
+use crate::engine::LifeEngine;
+use std::mem;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How fast a running simulation advances
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Speed {
+    /// Generations per second
+    Rate(u32),
+    /// As many generations as the engine can compute
+    Max,
+}
+
+/// Generation computed by the background thread, as the cells it changed
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub generation: u64,
+    /// Number of random numbers drawn so far, see `LifeEngine::rng_position`
+    pub rng_position: u128,
+    /// Cells that differ from the previous generation, with their index
+    pub changes: Vec<(usize, Cell)>,
+    /// Tiles recomputed by the step, see `LifeEngine::active_tiles`
+    pub active_tiles: Option<(usize, usize)>,
+}
+
+/// Steps published by the background thread and not taken yet
+#[derive(Debug, Default)]
+struct Published {
+    /// Number of restores the engine had been through when taking the steps, to tell steps
+    /// taken before the latest one apart
+    version: u64,
+    steps: Vec<Step>,
+}
+
+enum Command {
+    /// Steps at the given speed, or stops stepping if `None`
+    Run(Option<Speed>),
+    /// Puts the engine in the given state, with the number of restores sent so far
+    Restore {
+        cells: Vec<Cell>,
+        generation: u64,
+        rng_position: u128,
+        version: u64,
+    },
+}
+
+/// Engine stepped on a background thread, so slow generations don't hold up the thread
+/// showing it. Each step publishes the cells it changed, which are kept until taken so every
+/// generation can be recorded.
+///
+/// The thread ends when the simulation is dropped.
+pub struct Simulation {
+    commands: Sender<Command>,
+    published: Arc<Mutex<Published>>,
+    /// Number of restores sent to the thread
+    version: u64,
+}
+
+impl Simulation {
+    /// Starts a paused simulation of `engine` on a new thread
+    pub fn start(engine: Box<dyn LifeEngine>) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let published = Arc::new(Mutex::new(Published::default()));
+        let shared = Arc::clone(&published);
+        thread::spawn(move || work(engine, receiver, shared));
+        Self {
+            commands,
+            published,
+            version: 0,
+        }
+    }
+    /// Starts stepping at `speed`, or changes the speed if already running
+    pub fn run(&self, speed: Speed) {
+        // The thread only stops if it panicked, leaving nothing to be done about it
+        let _ = self.commands.send(Command::Run(Some(speed)));
+    }
+    /// Stops stepping after the step in progress, if any
+    pub fn pause(&self) {
+        let _ = self.commands.send(Command::Run(None));
+    }
+    /// Puts the simulated engine in the state of `engine`, such as after it was edited.
+    /// Steps published before are never returned by `take_steps`
+    pub fn restore(&mut self, engine: &dyn LifeEngine) {
+        self.version += 1;
+        let _ = self.commands.send(Command::Restore {
+            cells: engine.cells().into_owned(),
+            generation: engine.generation(),
+            rng_position: engine.rng_position(),
+            version: self.version,
+        });
+    }
+    /// Steps published since the last call, oldest first, each changing the cells of the one
+    /// before and the first one those of the last restored state
+    pub fn take_steps(&self) -> Vec<Step> {
+        let Ok(mut published) = self.published.lock() else {
+            return vec![];
+        };
+        let steps = mem::take(&mut published.steps);
+        match published.version == self.version {
+            true => steps,
+            false => vec![],
+        }
+    }
+}
+
+/// Runs the commands sent to `engine` until the simulation is dropped, stepping it in between
+fn work(
+    mut engine: Box<dyn LifeEngine>,
+    commands: Receiver<Command>,
+    published: Arc<Mutex<Published>>,
+) {
+    let mut speed = None;
+    let mut next_step = Instant::now();
+    // Cells before the next step, to find those it changes
+    let mut previous = engine.cells().into_owned();
+    loop {
+        // Waits for a command while paused or until the next step is due
+        let command = match speed {
+            None => commands.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(Speed::Max) => commands.try_recv().map_err(|err| match err {
+                TryRecvError::Empty => RecvTimeoutError::Timeout,
+                TryRecvError::Disconnected => RecvTimeoutError::Disconnected,
+            }),
+            Some(Speed::Rate(_)) => {
+                commands.recv_timeout(next_step.saturating_duration_since(Instant::now()))
+            }
+        };
+        match command {
+            Ok(Command::Run(new_speed)) => {
+                speed = new_speed;
+                next_step = Instant::now();
+            }
+            Ok(Command::Restore {
+                cells,
+                generation,
+                rng_position,
+                version,
+            }) => {
+                previous = cells.clone();
+                engine.restore(cells, generation, rng_position);
+                let Ok(mut published) = published.lock() else {
+                    return;
+                };
+                // Steps from the state before can't follow the restored one
+                published.steps.clear();
+                published.version = version;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                engine.step();
+                if let Some(Speed::Rate(rate)) = speed {
+                    // Steps that fell behind aren't made up for, to avoid bursts after a slow
+                    // one
+                    next_step += Duration::from_secs_f64(1. / rate.max(1) as f64);
+                    next_step = next_step.max(Instant::now());
+                }
+                let cells = engine.cells();
+                let changes = cells
+                    .iter()
+                    .zip(previous.iter_mut())
+                    .enumerate()
+                    .filter(|(_, (cell, previous))| cell != previous)
+                    .map(|(idx, (cell, previous))| {
+                        *previous = cell.clone();
+                        (idx, cell.clone())
+                    })
+                    .collect::<Vec<(usize, Cell)>>();
+                let step = Step {
+                    generation: engine.generation(),
+                    rng_position: engine.rng_position(),
+                    changes,
+                    active_tiles: engine.active_tiles(),
+                };
+                let Ok(mut published) = published.lock() else {
+                    return;
+                };
+                published.steps.push(step);
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
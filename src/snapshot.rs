@@ -0,0 +1,228 @@
+use crate::cell::Cell;
+use crate::engine::LifeEngine;
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::neighbourhood::Neighbourhood;
+use crate::rule::{LifeRule, Rule};
+use crate::scheme::UpdateScheme;
+use crate::table::RuleTable;
+use std::fs;
+use std::path::Path;
+
+/// First line of every snapshot, with the version of the format
+const HEADER: &str = "#GameOfLife snapshot 1";
+/// Largest width and height of a loaded snapshot
+const MAX_SIDE: usize = 1 << 16;
+/// Most cells a loaded snapshot may have, so a corrupt header can't exhaust memory
+const MAX_CELLS: usize = 1 << 24;
+
+/// Text holding everything needed to resume `engine` exactly where it is.
+///
+/// After the header come `key value` lines for the settings of the grid, then `cells` and one
+/// line per row of the grid. A row is a list of runs `count*state@changed`, where the count is
+/// left out for single cells and `@changed`, the generation the cells last changed state in,
+/// for cells that never did. Rule tables are included whole, after a `rule-table` line giving
+/// their number of lines.
+pub fn to_snapshot(engine: &dyn LifeEngine) -> String {
+    let mut lines = vec![
+        HEADER.to_string(),
+        format!("width {}", engine.width()),
+        format!("height {}", engine.height()),
+        format!("topology {}", engine.topology()),
+        format!("generation {}", engine.generation()),
+        format!("rule {}", engine.rule()),
+    ];
+    match engine.rule() {
+        Rule::Life(rule) => {
+            if let Neighbourhood::Custom(_) = rule.neighbourhood() {
+                lines.push(format!("neighbourhood {}", rule.neighbourhood()));
+            }
+        }
+        Rule::Table(table) => {
+            lines.push(format!("rule-table {}", table.source().lines().count()));
+            lines.extend(table.source().lines().map(|line| line.to_string()));
+        }
+        _ => {}
+    }
+    lines.push(format!("scheme {}", engine.scheme()));
+    lines.push(format!("seed {}", engine.seed()));
+    lines.push(format!("rng {}", engine.rng_position()));
+    lines.push("cells".to_string());
+    for row in engine.cells().chunks(engine.width()) {
+        let mut runs: Vec<(usize, &Cell)> = vec![];
+        for cell in row {
+            match runs.last_mut() {
+                Some((count, last)) if *last == cell => *count += 1,
+                _ => runs.push((1, cell)),
+            }
+        }
+        let runs = runs
+            .into_iter()
+            .map(|(count, cell)| {
+                let mut run = match count {
+                    1 => cell.state().to_string(),
+                    _ => format!("{}*{}", count, cell.state()),
+                };
+                if let Some(changed_at) = cell.changed_at() {
+                    run.push_str(&format!("@{}", changed_at));
+                }
+                run
+            })
+            .collect::<Vec<String>>();
+        lines.push(runs.join(" "));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Grid in the state a snapshot was taken in
+pub fn from_snapshot(s: &str) -> Result<Grid> {
+    let mut lines = s.lines();
+    if lines.next().map(|line| line.trim()) != Some(HEADER) {
+        return Err(Error::Parse(
+            "not a snapshot, or one of an unknown version".to_string(),
+        ));
+    }
+    let mut width: Option<usize> = None;
+    let mut height: Option<usize> = None;
+    let mut generation = 0;
+    let mut rulestring = None;
+    let mut neighbourhood = None;
+    let mut table = None;
+    let mut scheme = UpdateScheme::Synchronous;
+    let mut seed = 0;
+    let mut rng_position = 0;
+    while let Some(line) = lines.next() {
+        let (key, value) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        match key {
+            "width" => width = Some(parse_number(key, value)?),
+            "height" => height = Some(parse_number(key, value)?),
+            "topology" if value == "bounded" => {}
+            "topology" => return Err(Error::Parse(format!("unsupported topology '{}'", value))),
+            "generation" => generation = parse_number(key, value)?,
+            "rule" => rulestring = Some(value.to_string()),
+            "neighbourhood" => neighbourhood = Some(value.parse::<Neighbourhood>()?),
+            "rule-table" => {
+                let count = parse_number(key, value)?;
+                let source = lines.by_ref().take(count).collect::<Vec<&str>>();
+                table = Some(source.join("\n").parse::<RuleTable>()?);
+            }
+            "scheme" => scheme = value.parse::<UpdateScheme>()?,
+            "seed" => seed = parse_number(key, value)?,
+            "rng" => rng_position = parse_number(key, value)?,
+            "cells" => break,
+            _ => {
+                return Err(Error::Parse(format!(
+                    "unexpected line '{}' in snapshot",
+                    line
+                )))
+            }
+        }
+    }
+    let (width, height, rulestring) = match (width, height, rulestring) {
+        (Some(width), Some(height), Some(rulestring)) if width > 0 && height > 0 => {
+            (width, height, rulestring)
+        }
+        _ => {
+            return Err(Error::Parse(
+                "snapshot needs a width, a height and a rule".to_string(),
+            ))
+        }
+    };
+    let rule = match (table, neighbourhood) {
+        (Some(table), _) => Rule::Table(table),
+        (None, Some(neighbourhood)) => Rule::Life(LifeRule::parse_for(&rulestring, neighbourhood)?),
+        (None, None) => rulestring.parse::<Rule>()?,
+    };
+
+    // The size of the grid is capped, and runs are checked against it before any of them is
+    // expanded, so neither a corrupt header nor a corrupt count can take up unbounded memory
+    width
+        .checked_mul(height)
+        .filter(|&cells| width <= MAX_SIDE && height <= MAX_SIDE && cells <= MAX_CELLS)
+        .ok_or_else(|| {
+            Error::Parse(format!(
+                "snapshot of {}x{} cells is larger than {} cells or {} cells a side",
+                width, height, MAX_CELLS, MAX_SIDE
+            ))
+        })?;
+    let mut runs: Vec<(usize, Cell)> = vec![];
+    let mut rows = 0;
+    for (y, line) in lines.take(height).enumerate() {
+        let mut row_cells: usize = 0;
+        for run in line.split_whitespace() {
+            let (count, cell) = run.split_once('*').unwrap_or(("1", run));
+            let (state, changed_at) = match cell.split_once('@') {
+                Some((state, changed_at)) => (state, Some(parse_number("cell", changed_at)?)),
+                None => (cell, None),
+            };
+            let state = parse_number("cell", state)?;
+            if state > rule.colours() {
+                return Err(Error::Parse(format!(
+                    "state {} in row {} of the snapshot isn't one of the rule",
+                    state, y
+                )));
+            }
+            if let Some(changed_at) = changed_at.filter(|&changed_at| changed_at > generation) {
+                return Err(Error::Parse(format!(
+                    "cell in row {} of the snapshot changed in generation {}, after generation {}",
+                    y, changed_at, generation
+                )));
+            }
+            let count = parse_number::<usize>("cell", count)?;
+            row_cells = row_cells
+                .checked_add(count)
+                .filter(|&row_cells| row_cells <= width)
+                .ok_or_else(|| {
+                    Error::Parse(format!(
+                        "row {} of the snapshot has more than {} cells",
+                        y, width
+                    ))
+                })?;
+            runs.push((count, Cell::from_parts(state, changed_at)));
+        }
+        if row_cells != width {
+            return Err(Error::Parse(format!(
+                "row {} of the snapshot doesn't have {} cells",
+                y, width
+            )));
+        }
+        rows += 1;
+    }
+    if rows != height {
+        return Err(Error::Parse(format!(
+            "snapshot doesn't have {} rows",
+            height
+        )));
+    }
+    let cells = runs
+        .into_iter()
+        .flat_map(|(count, cell)| std::iter::repeat_n(cell, count))
+        .collect::<Vec<Cell>>();
+    let mut grid = Grid::with_rule(width, height, rule).with_scheme(scheme, seed)?;
+    grid.restore(cells, generation, rng_position);
+    Ok(grid)
+}
+
+/// Writes a snapshot of `engine` to a file
+pub fn save(engine: &dyn LifeEngine, path: &Path) -> Result<()> {
+    fs::write(path, to_snapshot(engine)).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Reads the grid of a snapshot file
+pub fn load(path: &Path) -> Result<Grid> {
+    let contents = fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    from_snapshot(&contents)
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| Error::Parse(format!("invalid {} '{}' in snapshot", key, value)))
+}
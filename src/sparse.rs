@@ -0,0 +1,179 @@
+use crate::cell::Cell;
+use crate::engine::LifeEngine;
+use crate::error::{Error, Result};
+use crate::rule::{LifeRule, Rule, MAX_COLOURS};
+use crate::types::Point;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Universe storing only its live cells, so the work of a step grows with the population
+/// rather than the area. Only life-like rules without births on 0 neighbours are supported, as
+/// other rules can change cells far from any live one.
+#[derive(Debug, Clone)]
+pub struct SparseGrid {
+    width: usize,
+    height: usize,
+    generation: u64,
+    rule: Rule,
+    /// Neighbourhood of the rule, cached to avoid rebuilding it every step
+    neighbour_offsets: Vec<(isize, isize)>,
+    /// Live cells by position
+    cells: HashMap<Point, Cell>,
+}
+
+impl SparseGrid {
+    pub fn with_rule(width: usize, height: usize, rule: &Rule) -> Result<Self> {
+        let neighbour_offsets = match rule {
+            Rule::Life(life) if !life.next_state(false, 0) => life.neighbourhood().offsets(),
+            _ => {
+                return Err(Error::InvalidArgument {
+                    name: "--rule".to_string(),
+                    value: rule.to_string(),
+                    expected: "a life-like rule without B0 with the sparse backend".to_string(),
+                })
+            }
+        };
+        Ok(Self {
+            width,
+            height,
+            generation: 0,
+            rule: rule.clone(),
+            neighbour_offsets,
+            cells: HashMap::new(),
+        })
+    }
+    fn life_rule(&self) -> &LifeRule {
+        match &self.rule {
+            Rule::Life(rule) => rule,
+            _ => unreachable!("sparse grids only hold life-like rules"),
+        }
+    }
+    /// Cell whose neighbour at `offset` is `pos`, `None` if it is outside the grid
+    fn neighbour_of(&self, pos: Point, offset: (isize, isize)) -> Option<Point> {
+        let x = pos.x as isize - offset.0;
+        let y = pos.y as isize - offset.1;
+        if x < 0 || y < 0 || x >= self.width as isize || y >= self.height as isize {
+            return None;
+        }
+        Some(Point {
+            x: x as usize,
+            y: y as usize,
+        })
+    }
+}
+
+impl LifeEngine for SparseGrid {
+    fn width(&self) -> usize {
+        self.width
+    }
+    fn height(&self) -> usize {
+        self.height
+    }
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// Dead cells don't keep the generation they died in
+    fn get_cell(&self, pos: Point) -> Option<Cell> {
+        if pos.x >= self.width || pos.y >= self.height {
+            return None;
+        }
+        Some(
+            self.cells
+                .get(&pos)
+                .cloned()
+                .unwrap_or_else(|| Cell::new(false)),
+        )
+    }
+    fn set_cell(&mut self, pos: Point, state: u8) -> Result<()> {
+        if pos.x >= self.width || pos.y >= self.height {
+            return Err(Error::OutOfBounds {
+                x: pos.x,
+                y: pos.y,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        self.rule.check_state(state)?;
+        match state {
+            0 => {
+                self.cells.remove(&pos);
+            }
+            state if self.cells.get(&pos).map(|cell| cell.state()) != Some(state) => {
+                self.cells
+                    .insert(pos, Cell::changed(state, self.generation));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    fn clone_engine(&self) -> Box<dyn LifeEngine> {
+        Box::new(self.clone())
+    }
+    fn cells(&self) -> Cow<'_, [Cell]> {
+        let mut cells = vec![Cell::new(false); self.width * self.height];
+        for (pos, cell) in self.cells.iter() {
+            cells[pos.y * self.width + pos.x] = cell.clone();
+        }
+        Cow::Owned(cells)
+    }
+    fn restore(&mut self, cells: Vec<Cell>, generation: u64, _rng_position: u128) {
+        self.cells = cells
+            .into_iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_alive())
+            .map(|(idx, cell)| {
+                let pos = Point {
+                    x: idx % self.width,
+                    y: idx / self.width,
+                };
+                (pos, cell)
+            })
+            .collect();
+        self.generation = generation;
+    }
+    fn step(&mut self) {
+        // Live neighbours of every cell next to a live one, and how many have each colour
+        let mut neighbours: HashMap<Point, (usize, [usize; MAX_COLOURS + 1])> = HashMap::new();
+        for (&pos, cell) in self.cells.iter() {
+            for &offset in self.neighbour_offsets.iter() {
+                if let Some(neighbour) = self.neighbour_of(pos, offset) {
+                    let (count, colour_counts) = neighbours.entry(neighbour).or_default();
+                    *count += 1;
+                    colour_counts[(cell.state() as usize).min(MAX_COLOURS)] += 1;
+                }
+            }
+        }
+        let rule = self.life_rule();
+        let generation = self.generation + 1;
+        let mut next = HashMap::with_capacity(self.cells.len());
+        // Live cells without live neighbours don't show up in `neighbours`
+        for (&pos, cell) in self.cells.iter() {
+            if !neighbours.contains_key(&pos) && rule.next_state(true, 0) {
+                next.insert(pos, cell.clone());
+            }
+        }
+        for (pos, (count, colour_counts)) in neighbours {
+            match self.cells.get(&pos) {
+                Some(cell) if rule.next_state(true, count) => {
+                    next.insert(pos, cell.clone());
+                }
+                None if rule.next_state(false, count) => {
+                    let colour = rule.newborn_colour(&colour_counts);
+                    next.insert(pos, Cell::changed(colour, generation));
+                }
+                _ => {}
+            }
+        }
+        self.cells = next;
+        self.generation = generation;
+    }
+    fn live_cells(&self) -> Box<dyn Iterator<Item = (Point, u8)> + '_> {
+        Box::new(self.cells.iter().map(|(&pos, cell)| (pos, cell.state())))
+    }
+    fn population(&self) -> usize {
+        self.cells.len()
+    }
+}
@@ -0,0 +1,29 @@
+use crate::engine::LifeEngine;
+
+/// Population figures of a grid at its current generation
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    pub generation: u64,
+    /// Number of live cells
+    pub population: usize,
+    /// Live cells of each colour, starting with the first colour
+    pub colour_populations: Vec<usize>,
+    /// Tiles recomputed by the last step out of all the tiles, for engines that track them
+    pub active_tiles: Option<(usize, usize)>,
+}
+
+impl Statistics {
+    pub fn new(engine: &dyn LifeEngine) -> Self {
+        let mut colour_populations = vec![0; engine.rule().colours() as usize];
+        for (_, state) in engine.live_cells() {
+            let colour = (state as usize).min(colour_populations.len());
+            colour_populations[colour - 1] += 1;
+        }
+        Self {
+            generation: engine.generation(),
+            population: colour_populations.iter().sum(),
+            colour_populations,
+            active_tiles: engine.active_tiles(),
+        }
+    }
+}
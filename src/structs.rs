@@ -1,3 +1,5 @@
+#![allow(clippy::identity_op)]
+
 //Second Glider Axis 180
 const A: usize = 80 - 0; // 51 es distancia par | 50 es distancia impar
 const B: usize = 80; // 51 es distancia par | 50 es distancia impar
@@ -5738,3 +5740,119 @@ pub const GLIDER_100_COLLISION_AH: [(usize, usize); 1000] = [
     (4 + 90 + E, 3 + 00 + E),
     (3 + 90 + E, 4 + 00 + E),
 ];
+
+pub const WIREWORLD_CLOCK: [(usize, usize, u8); 42] = [
+    /*
+    WireWorld clock: an electron (head 1, tail 2) going round a loop of conductor (3),
+    sending a pulse down the wire every 14 generations, the length of the loop
+        3213333
+        3     3333333333333333333333333
+        3     3
+        3333333
+    */
+    (10, 10, 3),
+    (11, 10, 2),
+    (12, 10, 1),
+    (13, 10, 3),
+    (14, 10, 3),
+    (15, 10, 3),
+    (16, 10, 3),
+    (10, 11, 3),
+    (16, 11, 3),
+    (17, 11, 3),
+    (18, 11, 3),
+    (19, 11, 3),
+    (20, 11, 3),
+    (21, 11, 3),
+    (22, 11, 3),
+    (23, 11, 3),
+    (24, 11, 3),
+    (25, 11, 3),
+    (26, 11, 3),
+    (27, 11, 3),
+    (28, 11, 3),
+    (29, 11, 3),
+    (30, 11, 3),
+    (31, 11, 3),
+    (32, 11, 3),
+    (33, 11, 3),
+    (34, 11, 3),
+    (35, 11, 3),
+    (36, 11, 3),
+    (37, 11, 3),
+    (38, 11, 3),
+    (39, 11, 3),
+    (40, 11, 3),
+    (10, 12, 3),
+    (16, 12, 3),
+    (10, 13, 3),
+    (11, 13, 3),
+    (12, 13, 3),
+    (13, 13, 3),
+    (14, 13, 3),
+    (15, 13, 3),
+    (16, 13, 3),
+];
+
+/// Two state patterns by the name they are loaded and stamped with
+pub const PATTERNS: [(&str, &[(usize, usize)]); 51] = [
+    ("blinker", &BLINKER),
+    ("toad", &TOAD),
+    ("gliderA", &GLIDER_A),
+    ("gliderB", &GLIDER_B),
+    ("gliderC", &GLIDER_C),
+    ("gliderD", &GLIDER_D),
+    ("glider-gun", &GLIDER_GUN),
+    ("collisionAAI180", &GLIDER_COLLISION_AAI180),
+    ("collisionABI180", &GLIDER_COLLISION_ABI180),
+    ("collisionACI180", &GLIDER_COLLISION_ACI180),
+    ("collisionADI180", &GLIDER_COLLISION_ADI180),
+    ("collisionBBI180", &GLIDER_COLLISION_BBI180),
+    ("collisionBCI180", &GLIDER_COLLISION_BCI180),
+    ("collisionBDI180", &GLIDER_COLLISION_BDI180),
+    ("collisionCCI180", &GLIDER_COLLISION_CCI180),
+    ("collisionCDI180", &GLIDER_COLLISION_CDI180),
+    ("collisionDDI180", &GLIDER_COLLISION_DDI180),
+    ("collisionAAP180", &GLIDER_COLLISION_AAP180),
+    ("collisionABP180", &GLIDER_COLLISION_ABP180),
+    ("collisionACP180", &GLIDER_COLLISION_ACP180),
+    ("collisionADP180", &GLIDER_COLLISION_ADP180),
+    ("collisionBBP180", &GLIDER_COLLISION_BBP180),
+    ("collisionBCP180", &GLIDER_COLLISION_BCP180),
+    ("collisionBDP180", &GLIDER_COLLISION_BDP180),
+    ("collisionCCP180", &GLIDER_COLLISION_CCP180),
+    ("collisionCDP180", &GLIDER_COLLISION_CDP180),
+    ("collisionDDP180", &GLIDER_COLLISION_DDP180),
+    ("collisionAAI90", &GLIDER_COLLISION_AAI90),
+    ("collisionABI90", &GLIDER_COLLISION_ABI90),
+    ("collisionACI90", &GLIDER_COLLISION_ACI90),
+    ("collisionADI90", &GLIDER_COLLISION_ADI90),
+    ("collisionBBI90", &GLIDER_COLLISION_BBI90),
+    ("collisionBCI90", &GLIDER_COLLISION_BCI90),
+    ("collisionBDI90", &GLIDER_COLLISION_BDI90),
+    ("collisionCCI90", &GLIDER_COLLISION_CCI90),
+    ("collisionCDI90", &GLIDER_COLLISION_CDI90),
+    ("collisionDDI90", &GLIDER_COLLISION_DDI90),
+    ("collisionAAP90", &GLIDER_COLLISION_AAP90),
+    ("collisionABP90", &GLIDER_COLLISION_ABP90),
+    ("collisionACP90", &GLIDER_COLLISION_ACP90),
+    ("collisionADP90", &GLIDER_COLLISION_ADP90),
+    ("collisionBBP90", &GLIDER_COLLISION_BBP90),
+    ("collisionBCP90", &GLIDER_COLLISION_BCP90),
+    ("collisionBDP90", &GLIDER_COLLISION_BDP90),
+    ("collisionCCP90", &GLIDER_COLLISION_CCP90),
+    ("collisionCDP90", &GLIDER_COLLISION_CDP90),
+    ("collisionDDP90", &GLIDER_COLLISION_DDP90),
+    ("100collisionAA", &GLIDER_100_COLLISION_AA),
+    ("100collisionAC", &GLIDER_100_COLLISION_AC),
+    ("100collisionAH1", &GLIDER_100_COLLISION_AH1),
+    ("100collisionAH", &GLIDER_100_COLLISION_AH),
+];
+
+/// Cells of the two state pattern called `name` in `PATTERNS`
+pub fn pattern_named(name: &str) -> Option<&'static [(usize, usize)]> {
+    PATTERNS
+        .iter()
+        .find(|(pattern_name, _)| *pattern_name == name)
+        .map(|&(_, cells)| cells)
+}
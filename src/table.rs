@@ -0,0 +1,452 @@
+use rayon::prelude::*;
+use std::str::FromStr;
+
+/// Tables with at most this many configurations are turned into a lookup table when loaded
+const LOOKUP_LIMIT: usize = 1 << 20;
+
+/// WireWorld: 0 is empty, 1 an electron head, 2 an electron tail and 3 a conductor
+const WIREWORLD: &str = "@RULE WireWorld
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={0,1,2,3}
+var c={0,1,2,3}
+var d={0,1,2,3}
+var e={0,1,2,3}
+var f={0,1,2,3}
+var g={0,1,2,3}
+var h={0,1,2,3}
+var o={0,2,3}
+var p={0,2,3}
+var q={0,2,3}
+var r={0,2,3}
+var s={0,2,3}
+var t={0,2,3}
+var u={0,2,3}
+# Electron heads become tails
+1,a,b,c,d,e,f,g,h,2
+# Electron tails become conductor
+2,a,b,c,d,e,f,g,h,3
+# Conductor next to one or two electron heads becomes a head
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+@COLORS
+0 48 48 48
+1 0 128 255
+2 255 255 255
+3 255 128 0
+";
+
+/// Neighbourhood of a rule table
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableNeighbourhood {
+    /// Neighbours listed clockwise from north: N, NE, E, SE, S, SW, W, NW
+    Moore,
+    /// Neighbours listed clockwise from north: N, E, S, W
+    VonNeumann,
+}
+
+impl TableNeighbourhood {
+    /// Offsets (x, y) of the neighbours in the order transitions list them
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            TableNeighbourhood::Moore => vec![
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+            ],
+            TableNeighbourhood::VonNeumann => vec![(0, -1), (1, 0), (0, 1), (-1, 0)],
+        }
+    }
+}
+
+/// Symmetries under which a transition also applies
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Symmetry {
+    /// Each listed arrangement of the neighbours is tried
+    Arrangements(Vec<Vec<usize>>),
+    /// Neighbours match in any order
+    Permute,
+}
+
+/// State or variable in a transition
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Term {
+    State(u8),
+    /// Index of a variable, all occurrences in a transition take the same value
+    Var(usize),
+}
+
+/// Inputs are the cell followed by its neighbours
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Transition {
+    inputs: Vec<Term>,
+    output: Term,
+}
+
+/// Multi-state rule given as a transition table in Golly's `@TABLE` format. Transitions are
+/// tried in order, and cells matching none keep their state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleTable {
+    name: String,
+    states: u8,
+    neighbourhood: TableNeighbourhood,
+    symmetry: Symmetry,
+    /// Values of each variable
+    vars: Vec<Vec<u8>>,
+    transitions: Vec<Transition>,
+    /// Colour of each state from the `@COLORS` section
+    colors: Vec<Option<(u8, u8, u8)>>,
+    /// Next state of every configuration, for tables small enough
+    lookup: Option<Vec<u8>>,
+    /// Text the table was parsed from
+    source: String,
+}
+
+impl RuleTable {
+    pub fn wireworld() -> Self {
+        WIREWORLD
+            .parse()
+            .expect("built-in WireWorld table is valid")
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Text of the `.rule` file the table was parsed from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+    /// Number of states, including the empty state 0
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+    pub fn neighbourhood(&self) -> TableNeighbourhood {
+        self.neighbourhood
+    }
+    /// RGB colour the table asks a state to be drawn with
+    pub fn color(&self, state: u8) -> Option<(u8, u8, u8)> {
+        self.colors.get(state as usize).copied().flatten()
+    }
+    /// Next state given the states of the cell followed by its neighbours, in the order of
+    /// `TableNeighbourhood::offsets`
+    pub fn next_state(&self, states: &[u8]) -> u8 {
+        match &self.lookup {
+            Some(lookup) => lookup[self.config_index(states)],
+            None => self.evaluate(states),
+        }
+    }
+    fn config_index(&self, states: &[u8]) -> usize {
+        states
+            .iter()
+            .rev()
+            .fold(0, |idx, &state| idx * self.states as usize + state as usize)
+    }
+    /// Runs through the transitions looking for the first one that matches
+    fn evaluate(&self, states: &[u8]) -> u8 {
+        let mut bindings = vec![None; self.vars.len()];
+        for transition in self.transitions.iter() {
+            let matched = match &self.symmetry {
+                Symmetry::Arrangements(arrangements) => arrangements.iter().any(|arrangement| {
+                    bindings.iter_mut().for_each(|binding| *binding = None);
+                    self.bind(transition.inputs[0], states[0], &mut bindings)
+                        && arrangement.iter().enumerate().all(|(input, &neighbour)| {
+                            self.bind(
+                                transition.inputs[input + 1],
+                                states[neighbour + 1],
+                                &mut bindings,
+                            )
+                        })
+                }),
+                Symmetry::Permute => {
+                    bindings.iter_mut().for_each(|binding| *binding = None);
+                    let mut used = vec![false; states.len() - 1];
+                    self.bind(transition.inputs[0], states[0], &mut bindings)
+                        && self.bind_any_order(
+                            &transition.inputs[1..],
+                            &states[1..],
+                            &mut used,
+                            &mut bindings,
+                        )
+                }
+            };
+            if matched {
+                return match transition.output {
+                    Term::State(state) => state,
+                    Term::Var(var) => bindings[var].unwrap_or(states[0]),
+                };
+            }
+        }
+        states[0]
+    }
+    /// Whether `state` matches `term`, binding the variable if it wasn't yet
+    fn bind(&self, term: Term, state: u8, bindings: &mut [Option<u8>]) -> bool {
+        match term {
+            Term::State(expected) => expected == state,
+            Term::Var(var) => match bindings[var] {
+                Some(bound) => bound == state,
+                None if self.vars[var].contains(&state) => {
+                    bindings[var] = Some(state);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+    /// Matches the remaining inputs against unused neighbours in any order, backtracking
+    fn bind_any_order(
+        &self,
+        inputs: &[Term],
+        states: &[u8],
+        used: &mut [bool],
+        bindings: &mut [Option<u8>],
+    ) -> bool {
+        let (&input, rest) = match inputs.split_first() {
+            Some(split) => split,
+            None => return true,
+        };
+        // Neighbours in the same state are interchangeable, so each state is tried once
+        let mut tried = [false; 256];
+        for neighbour in 0..states.len() {
+            if used[neighbour] || tried[states[neighbour] as usize] {
+                continue;
+            }
+            tried[states[neighbour] as usize] = true;
+            // Deeper levels undo their own bindings, so only this one needs restoring
+            let saved = match input {
+                Term::Var(var) => Some((var, bindings[var])),
+                Term::State(_) => None,
+            };
+            if self.bind(input, states[neighbour], bindings) {
+                used[neighbour] = true;
+                if self.bind_any_order(rest, states, used, bindings) {
+                    return true;
+                }
+                used[neighbour] = false;
+            }
+            if let Some((var, binding)) = saved {
+                bindings[var] = binding;
+            }
+        }
+        false
+    }
+    /// Evaluates every configuration ahead of time when there aren't too many of them
+    fn build_lookup(&mut self) {
+        let inputs = self.neighbourhood.offsets().len() as u32 + 1;
+        let configs = match (self.states as usize).checked_pow(inputs) {
+            Some(configs) if configs <= LOOKUP_LIMIT => configs,
+            _ => return,
+        };
+        let lookup = (0..configs)
+            .into_par_iter()
+            .map(|mut idx| {
+                let mut states = Vec::with_capacity(inputs as usize);
+                for _ in 0..inputs {
+                    states.push((idx % self.states as usize) as u8);
+                    idx /= self.states as usize;
+                }
+                self.evaluate(&states)
+            })
+            .collect::<Vec<u8>>();
+        self.lookup = Some(lookup);
+    }
+}
+
+impl FromStr for RuleTable {
+    type Err = String;
+
+    /// Parses a `.rule` file with `@TABLE` and optionally `@COLORS` sections, or a bare table
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::from("table");
+        let mut section = if s.contains("@TABLE") { "" } else { "@TABLE" };
+        let mut states = None;
+        let mut neighbourhood = TableNeighbourhood::Moore;
+        let mut symmetry_name = String::from("none");
+        let mut var_names: Vec<String> = vec![];
+        let mut vars: Vec<Vec<u8>> = vec![];
+        let mut lines = vec![];
+        let mut colors = vec![];
+
+        for line in s.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('@') {
+                let mut words = line.split_whitespace();
+                section = words.next().unwrap_or("");
+                if section == "@RULE" {
+                    name = words.next().unwrap_or("table").to_string();
+                }
+                continue;
+            }
+            match section {
+                "@TABLE" => {
+                    if let Some((key, value)) = line.split_once(':') {
+                        match key.trim() {
+                            "n_states" => {
+                                // A table needs a dead state and at least one other
+                                let count = value
+                                    .trim()
+                                    .parse::<u8>()
+                                    .ok()
+                                    .filter(|&count| count >= 2)
+                                    .ok_or_else(|| {
+                                        format!(
+                                            "invalid n_states '{}', expected 2 to 255",
+                                            value.trim()
+                                        )
+                                    })?;
+                                states = Some(count);
+                            }
+                            "neighborhood" => {
+                                neighbourhood = match value.trim() {
+                                    "Moore" => TableNeighbourhood::Moore,
+                                    "vonNeumann" => TableNeighbourhood::VonNeumann,
+                                    other => {
+                                        return Err(format!("unsupported neighborhood '{}'", other))
+                                    }
+                                }
+                            }
+                            "symmetries" => symmetry_name = value.trim().to_string(),
+                            other => return Err(format!("unknown table setting '{}'", other)),
+                        }
+                    } else if let Some(declaration) = line.strip_prefix("var ") {
+                        let (var, values) = declaration
+                            .split_once('=')
+                            .ok_or_else(|| format!("invalid variable '{}'", line))?;
+                        let mut expanded = vec![];
+                        for value in values.trim().trim_matches(['{', '}']).split(',') {
+                            let value = value.trim();
+                            match var_names.iter().position(|name| name == value) {
+                                Some(other) => expanded.extend(vars[other].iter().copied()),
+                                None => expanded.push(parse_state(value, u8::MAX)?),
+                            }
+                        }
+                        var_names.push(var.trim().to_string());
+                        vars.push(expanded);
+                    } else {
+                        lines.push(line.to_string());
+                    }
+                }
+                "@COLORS" => {
+                    let values = line
+                        .split_whitespace()
+                        .map(|value| value.parse::<u8>())
+                        .collect::<Result<Vec<u8>, _>>()
+                        .map_err(|_| format!("invalid color '{}'", line))?;
+                    if let [state, r, g, b] = values[..] {
+                        if colors.len() <= state as usize {
+                            colors.resize(state as usize + 1, None);
+                        }
+                        colors[state as usize] = Some((r, g, b));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let states = states.ok_or("rule table has no n_states")?;
+        let size = neighbourhood.offsets().len();
+        let symmetry = symmetry(&symmetry_name, neighbourhood)?;
+        let term = |token: &str| -> Result<Term, String> {
+            match var_names.iter().rposition(|name| name == token) {
+                Some(var) => Ok(Term::Var(var)),
+                None => Ok(Term::State(parse_state(token, states - 1)?)),
+            }
+        };
+        let mut transitions = vec![];
+        for line in lines {
+            let tokens = if line.contains(',') {
+                line.split(',')
+                    .map(|token| token.trim().to_string())
+                    .collect()
+            } else {
+                line.chars().map(|c| c.to_string()).collect::<Vec<String>>()
+            };
+            if tokens.len() != size + 2 {
+                return Err(format!(
+                    "transition '{}' should have {} entries",
+                    line,
+                    size + 2
+                ));
+            }
+            let inputs = tokens[..=size]
+                .iter()
+                .map(|token| term(token))
+                .collect::<Result<Vec<Term>, String>>()?;
+            let output = term(&tokens[size + 1])?;
+            if matches!(output, Term::Var(_)) && !inputs.contains(&output) {
+                return Err(format!("output of '{}' is an unbound variable", line));
+            }
+            transitions.push(Transition { inputs, output });
+        }
+
+        let mut table = Self {
+            name,
+            states,
+            neighbourhood,
+            symmetry,
+            vars,
+            transitions,
+            colors,
+            lookup: None,
+            source: s.to_string(),
+        };
+        table.build_lookup();
+        Ok(table)
+    }
+}
+
+fn parse_state(s: &str, max: u8) -> Result<u8, String> {
+    s.trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|&state| state <= max)
+        .ok_or_else(|| format!("invalid state '{}'", s.trim()))
+}
+
+/// Arrangements of the neighbours for a symmetry declaration
+fn symmetry(name: &str, neighbourhood: TableNeighbourhood) -> Result<Symmetry, String> {
+    let size = neighbourhood.offsets().len();
+    // Number of positions a quarter turn moves the neighbours by
+    let quarter = size / 4;
+    let rotate = |steps: usize| {
+        (0..size)
+            .map(|i| (i + steps) % size)
+            .collect::<Vec<usize>>()
+    };
+    let reflect = |arrangement: &Vec<usize>| {
+        arrangement
+            .iter()
+            .map(|&i| (size - i) % size)
+            .collect::<Vec<usize>>()
+    };
+    let rotations = |step: usize| (0..size).step_by(step).map(rotate).collect::<Vec<_>>();
+    let with_reflections = |arrangements: Vec<Vec<usize>>| {
+        let reflected = arrangements.iter().map(reflect).collect::<Vec<_>>();
+        arrangements
+            .into_iter()
+            .chain(reflected)
+            .collect::<Vec<_>>()
+    };
+    let arrangements = match name {
+        "none" => vec![rotate(0)],
+        "rotate4" => rotations(quarter),
+        "rotate8" if neighbourhood == TableNeighbourhood::Moore => rotations(1),
+        "reflect_horizontal" => with_reflections(vec![rotate(0)]),
+        "rotate4reflect" => with_reflections(rotations(quarter)),
+        "rotate8reflect" if neighbourhood == TableNeighbourhood::Moore => {
+            with_reflections(rotations(1))
+        }
+        "permute" => return Ok(Symmetry::Permute),
+        _ => return Err(format!("unsupported symmetries '{}'", name)),
+    };
+    Ok(Symmetry::Arrangements(arrangements))
+}
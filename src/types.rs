@@ -1,11 +1,14 @@
-#[derive(Debug, Copy, Clone)]
-pub struct Point{
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Point {
     pub x: usize,
     pub y: usize,
 }
 
-impl From<(usize, usize)> for Point{
-    fn from(item: (usize, usize)) -> Self{
-        Self {x: item.0, y: item.1}
+impl From<(usize, usize)> for Point {
+    fn from(item: (usize, usize)) -> Self {
+        Self {
+            x: item.0,
+            y: item.1,
+        }
     }
 }
@@ -0,0 +1,22 @@
+//! Starting states shared by the tests comparing two ways of computing a generation.
+
+use game_of_life::{Grid, Rule};
+
+/// Rules the random soups are run under, covering life-like rules of every neighbourhood
+/// and multi-colour rules
+pub const RULES: [&str; 8] = [
+    "B3/S23",
+    "B36/S23",
+    "B2/S",
+    "B3678/S34678",
+    "B2/S34H",
+    "B3/S23V",
+    "Immigration",
+    "QuadLife",
+];
+
+/// Grid of `width` by `height` with about a third of its cells alive, in random colours of the
+/// rule
+pub fn random_soup(width: usize, height: usize, rule: Rule, seed: u64) -> Grid {
+    Grid::with_rule(width, height, rule).with_random_soup(0.35, seed)
+}
@@ -0,0 +1,92 @@
+//! Runs the same starting states through every backend and checks they stay identical.
+
+mod common;
+
+use common::{random_soup, RULES};
+use game_of_life::engine::{Backend, LifeEngine};
+use game_of_life::structs::PATTERNS;
+use game_of_life::{Grid, Pattern, Rule};
+
+/// Generations every starting state is run for
+const GENERATIONS: u64 = 64;
+/// Empty cells left to the right of and below library patterns, so they have room to evolve
+const MARGIN: usize = 16;
+
+/// An engine of every backend supporting the rule of `grid`, each starting from its state
+fn engines(grid: &Grid) -> Vec<(Backend, Box<dyn LifeEngine>)> {
+    let engines = Backend::ALL
+        .iter()
+        .filter_map(|&backend| Some((backend, backend.engine(grid.clone()).ok()?)))
+        .collect::<Vec<_>>();
+    assert!(
+        engines.len() > 1,
+        "only one backend supports {}, nothing to compare",
+        grid.rule()
+    );
+    engines
+}
+
+/// Steps every engine in lockstep, failing at the first generation and cell in which one of
+/// them differs from the first
+fn assert_agree(name: &str, grid: Grid) {
+    let mut engines = engines(&grid);
+    for _ in 0..=GENERATIONS {
+        let (reference_backend, reference) = &engines[0];
+        let expected = reference.cells();
+        for (backend, engine) in engines[1..].iter() {
+            assert_eq!(
+                engine.generation(),
+                reference.generation(),
+                "{}: {} backend is at the wrong generation",
+                name,
+                backend
+            );
+            let cells = engine.cells();
+            let divergent = expected
+                .iter()
+                .zip(cells.iter())
+                .position(|(expected, cell)| expected.state() != cell.state());
+            if let Some(idx) = divergent {
+                panic!(
+                    "{}: {} and {} backends diverge in generation {} at cell ({}, {}), in state {} and {}",
+                    name,
+                    reference_backend,
+                    backend,
+                    reference.generation(),
+                    idx % reference.width(),
+                    idx / reference.width(),
+                    expected[idx].state(),
+                    cells[idx].state()
+                );
+            }
+        }
+        for (_, engine) in engines.iter_mut() {
+            engine.step();
+        }
+    }
+}
+
+#[test]
+fn random_soups_agree() {
+    for rulestring in RULES {
+        let rule = rulestring.parse::<Rule>().unwrap();
+        for seed in 0..4 {
+            let name = format!("{} soup {}", rulestring, seed);
+            assert_agree(&name, random_soup(48, 40, rule.clone(), seed));
+        }
+    }
+}
+
+#[test]
+fn library_patterns_agree() {
+    for (name, _) in PATTERNS.iter() {
+        let pattern = Pattern::named(name).unwrap();
+        let mut grid = Grid::with_rule(
+            pattern.width() + MARGIN,
+            pattern.height() + MARGIN,
+            Rule::conway(),
+        );
+        grid.set_states(pattern.cells()).unwrap();
+        assert_agree(name, grid);
+    }
+}
@@ -0,0 +1,103 @@
+//! Checks one-dimensional rules draw the spacetime diagrams they are known for, and that grids
+//! without cells can be stepped under any kind of rule.
+
+use game_of_life::{Grid, Point, Rule};
+
+/// Rows of the grid, live cells drawn as `#` and dead ones as `.`
+fn rows(grid: &Grid) -> Vec<String> {
+    (0..grid.height())
+        .map(|y| {
+            (0..grid.width())
+                .map(|x| match grid.cell(Point { x, y }).unwrap().is_alive() {
+                    true => '#',
+                    false => '.',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Spacetime diagram of `rulestring` after `generations`, grown from a single live cell at `x`
+/// on the top row
+fn diagram(
+    rulestring: &str,
+    width: usize,
+    height: usize,
+    x: usize,
+    generations: u64,
+) -> Vec<String> {
+    let mut grid = Grid::with_rule(width, height, rulestring.parse::<Rule>().unwrap());
+    grid.set_state(&[Point { x, y: 0 }]).unwrap();
+    grid.step_n(generations);
+    rows(&grid)
+}
+
+#[test]
+fn rule_30_grows_its_triangle() {
+    assert_eq!(
+        diagram("W30", 15, 6, 7, 5),
+        [
+            ".......#.......",
+            "......###......",
+            ".....##..#.....",
+            "....##.####....",
+            "...##..#...#...",
+            "..##.####.###..",
+        ]
+    );
+}
+
+#[test]
+fn rule_110_grows_to_the_left() {
+    assert_eq!(
+        diagram("W110", 10, 8, 8, 7),
+        [
+            "........#.",
+            ".......##.",
+            "......###.",
+            ".....##.#.",
+            "....#####.",
+            "...##...#.",
+            "..###..##.",
+            ".##.#.###.",
+        ]
+    );
+}
+
+#[test]
+fn full_diagrams_scroll_up() {
+    // The last 3 rows of the triangle of rule 30
+    assert_eq!(
+        diagram("W30", 15, 3, 7, 5),
+        ["....##.####....", "...##..#...#...", "..##.####.###.."]
+    );
+}
+
+#[test]
+fn grids_without_cells_can_be_stepped() {
+    let rules = [
+        "B3/S23",
+        "W30",
+        "T20R2",
+        "R2,C0,M0,S3..8,B5..7,NM",
+        "B2-a/S12",
+        "BBM",
+        "WireWorld",
+    ];
+    for rulestring in rules {
+        let rule = rulestring.parse::<Rule>().unwrap();
+        for (width, height) in [(0, 0), (5, 0), (0, 5)] {
+            let mut grid = Grid::with_rule(width, height, rule.clone());
+            grid.step_n(3);
+            assert_eq!(
+                grid.generation(),
+                3,
+                "{} on {}x{}",
+                rulestring,
+                width,
+                height
+            );
+            assert!(grid.cells().is_empty());
+        }
+    }
+}
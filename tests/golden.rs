@@ -0,0 +1,298 @@
+//! Regression suite recording how every pattern of the built-in library evolves, so engine
+//! changes that alter the outcome are caught.
+//!
+//! The cases run on every backend, so each of them is held to the same outputs.
+
+use game_of_life::engine::{Backend, LifeEngine};
+use game_of_life::structs::PATTERNS;
+use game_of_life::{Grid, Pattern, Rule};
+
+/// Empty cells left to the right of and below each pattern, so it has room to evolve
+const MARGIN: usize = 32;
+
+/// Corners of a bounding box, as `(min x, min y, max x, max y)`
+type BoundingBox = (usize, usize, usize, usize);
+
+/// Pattern, generation, population, bounding box and hash of the live cells, under B3/S23 on
+/// a grid just larger than the pattern.
+///
+/// Blinker and toad have period 2, gliders move a cell diagonally every 4 generations and the
+/// two guns of `glider-gun` emit a glider each every 30 generations.
+#[rustfmt::skip]
+const GOLDEN: &[(&str, u64, usize, Option<BoundingBox>, u64)] = &[
+    ("blinker", 0, 3, Some((4, 4, 4, 6)), 0xf484f7c74e057645),
+    ("blinker", 1, 3, Some((3, 5, 5, 5)), 0x084085029afb5b49),
+    ("blinker", 2, 3, Some((4, 4, 4, 6)), 0xf484f7c74e057645),
+    ("toad", 0, 6, Some((4, 3, 5, 6)), 0x4699cbcf28330e05),
+    ("toad", 1, 6, Some((3, 3, 6, 6)), 0x491853fac3768195),
+    ("toad", 2, 6, Some((4, 3, 5, 6)), 0x4699cbcf28330e05),
+    ("gliderA", 0, 5, Some((1, 2, 3, 4)), 0x09e6421a96a3d0b9),
+    ("gliderA", 4, 5, Some((2, 3, 4, 5)), 0x396e3220a2c95a4f),
+    ("gliderA", 8, 5, Some((3, 4, 5, 6)), 0xaff943cce8db3a39),
+    ("gliderB", 0, 5, Some((1, 2, 3, 4)), 0xfbfb4d994bd6e11a),
+    ("gliderB", 4, 5, Some((2, 3, 4, 5)), 0x1e28b098b919e8b6),
+    ("gliderB", 8, 5, Some((3, 4, 5, 6)), 0x75e2e721116f70ee),
+    ("gliderC", 0, 5, Some((1, 2, 3, 4)), 0x775b41cba9c57e27),
+    ("gliderC", 4, 5, Some((2, 3, 4, 5)), 0x54bcc91f3168cf8d),
+    ("gliderC", 8, 5, Some((3, 4, 5, 6)), 0xf55fb749c96b24c7),
+    ("gliderD", 0, 5, Some((1, 2, 3, 4)), 0x5ba0cc1a0fbb4864),
+    ("gliderD", 4, 5, Some((2, 3, 4, 5)), 0x70bebe30a35da3a4),
+    ("gliderD", 8, 5, Some((3, 4, 5, 6)), 0x16c178f52ab2bdb4),
+    ("glider-gun", 0, 72, Some((1, 1, 135, 108)), 0x3394748ed357e3b7),
+    ("glider-gun", 30, 82, Some((1, 1, 135, 108)), 0x90ffb7cd5b663948),
+    ("glider-gun", 60, 92, Some((1, 1, 135, 108)), 0x5c800ddd8b216159),
+    ("glider-gun", 90, 102, Some((1, 1, 135, 108)), 0x8b30264dd9cbc200),
+    ("glider-gun", 120, 112, Some((1, 1, 135, 108)), 0x098dd2c48ccb26d3),
+    ("collisionAAI180", 0, 10, Some((1, 2, 83, 84)), 0x528632c8e68e9999),
+    ("collisionAAI180", 100, 10, Some((26, 27, 58, 59)), 0xbd33cb319744bed1),
+    ("collisionAAI180", 150, 10, Some((39, 39, 45, 47)), 0x4bc6b9f3f03eb921),
+    ("collisionAAI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionABI180", 0, 10, Some((1, 2, 83, 84)), 0xe7733ee654426564),
+    ("collisionABI180", 100, 10, Some((26, 27, 58, 59)), 0xebfd28bc3254f01a),
+    ("collisionABI180", 150, 10, Some((39, 39, 45, 47)), 0xcb0e12621a21767e),
+    ("collisionABI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionACI180", 0, 10, Some((1, 2, 83, 84)), 0xe806dd5184a4000f),
+    ("collisionACI180", 100, 10, Some((26, 27, 58, 59)), 0x2941769a635461bb),
+    ("collisionACI180", 150, 10, Some((39, 39, 46, 46)), 0xaa89259fb2fac6c5),
+    ("collisionACI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionADI180", 0, 10, Some((1, 2, 83, 84)), 0x368ddad5a377d79a),
+    ("collisionADI180", 100, 10, Some((26, 27, 58, 59)), 0xeb9936608c0d2710),
+    ("collisionADI180", 150, 10, Some((39, 39, 46, 46)), 0x736f775a29604426),
+    ("collisionADI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionBBI180", 0, 10, Some((1, 2, 83, 84)), 0xa4515d4ebbbf6c0d),
+    ("collisionBBI180", 100, 10, Some((26, 27, 58, 59)), 0xd7b1f954f6e88fcd),
+    ("collisionBBI180", 150, 10, Some((39, 39, 45, 47)), 0x2f3f596a196ead65),
+    ("collisionBBI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionBCI180", 0, 10, Some((1, 2, 83, 84)), 0x0b78aa5a4b9a3fe2),
+    ("collisionBCI180", 100, 10, Some((26, 27, 58, 59)), 0x52c9c0dcbaa76be0),
+    ("collisionBCI180", 150, 10, Some((39, 39, 46, 46)), 0x1f250320bfa6825e),
+    ("collisionBCI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionBDI180", 0, 10, Some((1, 2, 83, 84)), 0x7e0a3f8b0fdd23e3),
+    ("collisionBDI180", 100, 10, Some((26, 27, 58, 59)), 0x2592a4dd17873ebf),
+    ("collisionBDI180", 150, 10, Some((39, 39, 46, 46)), 0xd63246a7a6445cc9),
+    ("collisionBDI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionCCI180", 0, 10, Some((1, 2, 83, 84)), 0xeb83d2f3fb9939a1),
+    ("collisionCCI180", 100, 10, Some((26, 27, 58, 59)), 0xee542d025fd1f129),
+    ("collisionCCI180", 150, 10, Some((38, 40, 46, 46)), 0x27efd7b67fcf24d5),
+    ("collisionCCI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionCDI180", 0, 10, Some((1, 2, 83, 84)), 0x1a993da2523a1410),
+    ("collisionCDI180", 100, 10, Some((26, 27, 58, 59)), 0xec3eb4ea8000c14e),
+    ("collisionCDI180", 150, 10, Some((38, 40, 46, 46)), 0x48807cc540281836),
+    ("collisionCDI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionDDI180", 0, 10, Some((1, 2, 83, 84)), 0x57ecc492b266814d),
+    ("collisionDDI180", 100, 10, Some((26, 27, 58, 59)), 0xee973b3490a161c5),
+    ("collisionDDI180", 150, 10, Some((38, 40, 46, 46)), 0x6b19ffdcffd9fc35),
+    ("collisionDDI180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionAAP180", 0, 10, Some((1, 2, 84, 85)), 0x58a8609d5acfa3df),
+    ("collisionAAP180", 100, 10, Some((26, 27, 59, 60)), 0x54274e1e5391294b),
+    ("collisionAAP180", 150, 10, Some((39, 39, 46, 48)), 0x7e70319e388719b5),
+    ("collisionAAP180", 200, 4, Some((42, 43, 43, 44)), 0x51cd9cb2d3faf4d3),
+    ("collisionABP180", 0, 10, Some((1, 2, 84, 85)), 0x2a56d75e9b779068),
+    ("collisionABP180", 100, 10, Some((26, 27, 59, 60)), 0x7d45ce1159f5db56),
+    ("collisionABP180", 150, 10, Some((39, 39, 46, 48)), 0xd2d40bf97d34f1b8),
+    ("collisionABP180", 200, 24, Some((36, 35, 48, 47)), 0x3193046e1fce3c25),
+    ("collisionACP180", 0, 10, Some((1, 2, 84, 85)), 0xf2c76fc2d1dbec79),
+    ("collisionACP180", 100, 10, Some((26, 27, 59, 60)), 0x95e31020c0229219),
+    ("collisionACP180", 150, 10, Some((39, 39, 47, 47)), 0x07bd1f8f9cfb5301),
+    ("collisionACP180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionADP180", 0, 10, Some((1, 2, 84, 85)), 0x01bee1566271687e),
+    ("collisionADP180", 100, 10, Some((26, 27, 59, 60)), 0xe013654a364cce08),
+    ("collisionADP180", 150, 10, Some((39, 39, 47, 47)), 0x49de454e898c5360),
+    ("collisionADP180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionBBP180", 0, 10, Some((1, 2, 84, 85)), 0x67ea84e3c98d1d81),
+    ("collisionBBP180", 100, 10, Some((26, 27, 59, 60)), 0x3c61e4b991566fc9),
+    ("collisionBBP180", 150, 10, Some((39, 39, 46, 48)), 0xb2944c22959ce9ff),
+    ("collisionBBP180", 200, 4, Some((42, 43, 43, 44)), 0x51cd9cb2d3faf4d3),
+    ("collisionBCP180", 0, 10, Some((1, 2, 84, 85)), 0xb472bad24c8704c0),
+    ("collisionBCP180", 100, 10, Some((26, 27, 59, 60)), 0xb2aa08eb0bb478de),
+    ("collisionBCP180", 150, 10, Some((39, 39, 47, 47)), 0x3b81857ac0d68a96),
+    ("collisionBCP180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionBDP180", 0, 10, Some((1, 2, 84, 85)), 0xf513db950496c1ef),
+    ("collisionBDP180", 100, 10, Some((26, 27, 59, 60)), 0xc5e67c4d15daf90f),
+    ("collisionBDP180", 150, 10, Some((39, 39, 47, 47)), 0xbe6476f0ea5f4e23),
+    ("collisionBDP180", 200, 0, None, 0xcbf29ce484222325),
+    ("collisionCCP180", 0, 10, Some((1, 2, 84, 85)), 0x9b95cb9d9a19197f),
+    ("collisionCCP180", 100, 10, Some((26, 27, 59, 60)), 0x5ed2b783e458cb73),
+    ("collisionCCP180", 150, 10, Some((38, 40, 47, 47)), 0x21ed8740a5760f11),
+    ("collisionCCP180", 200, 4, Some((42, 43, 43, 44)), 0x51cd9cb2d3faf4d3),
+    ("collisionCDP180", 0, 10, Some((1, 2, 84, 85)), 0x29d515e04017dc14),
+    ("collisionCDP180", 100, 10, Some((26, 27, 59, 60)), 0x45166aef7648e1fe),
+    ("collisionCDP180", 150, 10, Some((38, 40, 47, 47)), 0x57151feea64a39f0),
+    ("collisionCDP180", 200, 24, Some((34, 37, 46, 49)), 0x0a5589e41fb0a205),
+    ("collisionDDP180", 0, 10, Some((1, 2, 84, 85)), 0xd507f8f4e29b48f1),
+    ("collisionDDP180", 100, 10, Some((26, 27, 59, 60)), 0x3a18ae7add1dd74d),
+    ("collisionDDP180", 150, 10, Some((38, 40, 47, 47)), 0x0033a807783aa40f),
+    ("collisionDDP180", 200, 4, Some((42, 43, 43, 44)), 0x51cd9cb2d3faf4d3),
+    ("collisionAAI90", 0, 10, Some((11, 2, 15, 84)), 0xcc83a809cb8ee247),
+    ("collisionAAI90", 100, 10, Some((36, 27, 40, 59)), 0x4cb2980845ac04fd),
+    ("collisionAAI90", 150, 8, Some((46, 37, 47, 51)), 0x7dc19acb568244cd),
+    ("collisionAAI90", 200, 8, Some((46, 37, 47, 51)), 0x7dc19acb568244cd),
+    ("collisionABI90", 0, 10, Some((11, 2, 15, 84)), 0x7821d0efadab0534),
+    ("collisionABI90", 100, 10, Some((36, 27, 40, 59)), 0x3affc14d05529544),
+    ("collisionABI90", 150, 8, Some((46, 37, 47, 51)), 0x7dc19acb568244cd),
+    ("collisionABI90", 200, 8, Some((46, 37, 47, 51)), 0x7dc19acb568244cd),
+    ("collisionACI90", 0, 10, Some((11, 2, 15, 84)), 0x829fff4eed1ee6d3),
+    ("collisionACI90", 100, 10, Some((36, 27, 40, 59)), 0x099ff5acfa194571),
+    ("collisionACI90", 150, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionACI90", 200, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionADI90", 0, 10, Some((11, 2, 15, 84)), 0xd318265dda9ba626),
+    ("collisionADI90", 100, 10, Some((36, 27, 40, 59)), 0x825e3c0c2067410a),
+    ("collisionADI90", 150, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionADI90", 200, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionBBI90", 0, 10, Some((11, 2, 15, 84)), 0x22b32f42f44c4289),
+    ("collisionBBI90", 100, 10, Some((36, 27, 40, 59)), 0x22528c5ff1b0d7cf),
+    ("collisionBBI90", 150, 8, Some((46, 37, 47, 51)), 0x7dc19acb568244cd),
+    ("collisionBBI90", 200, 8, Some((46, 37, 47, 51)), 0x7dc19acb568244cd),
+    ("collisionBCI90", 0, 10, Some((11, 2, 15, 84)), 0xb103c8ed52926caa),
+    ("collisionBCI90", 100, 10, Some((36, 27, 40, 59)), 0xf4215ba1749acfe6),
+    ("collisionBCI90", 150, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionBCI90", 200, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionBDI90", 0, 10, Some((11, 2, 15, 84)), 0x608ba1de6515ad57),
+    ("collisionBDI90", 100, 10, Some((36, 27, 40, 59)), 0x3206f98381f94e35),
+    ("collisionBDI90", 150, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionBDI90", 200, 8, Some((46, 37, 47, 50)), 0x590498672fd4a721),
+    ("collisionCCI90", 0, 10, Some((11, 2, 15, 84)), 0xace3f566983231cd),
+    ("collisionCCI90", 100, 10, Some((36, 27, 40, 59)), 0xd75e6eb07dd5d753),
+    ("collisionCCI90", 150, 8, Some((46, 38, 47, 50)), 0x9091117d6bda59bd),
+    ("collisionCCI90", 200, 8, Some((46, 38, 47, 50)), 0x9091117d6bda59bd),
+    ("collisionCDI90", 0, 10, Some((11, 2, 15, 84)), 0xc1ffbeba224a51d4),
+    ("collisionCDI90", 100, 10, Some((36, 27, 40, 59)), 0xd12406ed3607d1f4),
+    ("collisionCDI90", 150, 8, Some((46, 38, 47, 50)), 0x9091117d6bda59bd),
+    ("collisionCDI90", 200, 8, Some((46, 38, 47, 50)), 0x9091117d6bda59bd),
+    ("collisionDDI90", 0, 10, Some((11, 2, 15, 84)), 0xd0949b65a01fb035),
+    ("collisionDDI90", 100, 10, Some((36, 27, 40, 59)), 0x5032dd0f50f7d51f),
+    ("collisionDDI90", 150, 8, Some((46, 38, 47, 50)), 0x9091117d6bda59bd),
+    ("collisionDDI90", 200, 8, Some((46, 38, 47, 50)), 0x9091117d6bda59bd),
+    ("collisionAAP90", 0, 10, Some((11, 2, 16, 85)), 0x399d06c3d2933129),
+    ("collisionAAP90", 100, 10, Some((36, 27, 41, 60)), 0xdf7ea4ea0451f5b3),
+    ("collisionAAP90", 150, 8, Some((47, 38, 48, 52)), 0xca0b0bbb733c23ad),
+    ("collisionAAP90", 200, 8, Some((47, 38, 48, 52)), 0xca0b0bbb733c23ad),
+    ("collisionABP90", 0, 10, Some((11, 2, 16, 85)), 0x61edef5923718b98),
+    ("collisionABP90", 100, 10, Some((36, 27, 41, 60)), 0xeb85edcef0b0bf2c),
+    ("collisionABP90", 150, 8, Some((47, 38, 48, 52)), 0xca0b0bbb733c23ad),
+    ("collisionABP90", 200, 8, Some((47, 38, 48, 52)), 0xca0b0bbb733c23ad),
+    ("collisionACP90", 0, 10, Some((11, 2, 16, 85)), 0x546d15ccef1dc0f9),
+    ("collisionACP90", 100, 10, Some((36, 27, 41, 60)), 0xa9dd258d670b6553),
+    ("collisionACP90", 150, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionACP90", 200, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionADP90", 0, 10, Some((11, 2, 16, 85)), 0xfeab237a81b1b8de),
+    ("collisionADP90", 100, 10, Some((36, 27, 41, 60)), 0xc0893088034d350a),
+    ("collisionADP90", 150, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionADP90", 200, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionBBP90", 0, 10, Some((11, 2, 16, 85)), 0x79c8e13364dc5601),
+    ("collisionBBP90", 100, 10, Some((36, 27, 41, 60)), 0xbaee3659782e3e23),
+    ("collisionBBP90", 150, 8, Some((47, 38, 48, 52)), 0xca0b0bbb733c23ad),
+    ("collisionBBP90", 200, 8, Some((47, 38, 48, 52)), 0xca0b0bbb733c23ad),
+    ("collisionBCP90", 0, 10, Some((11, 2, 16, 85)), 0x9a71a99605f9a92c),
+    ("collisionBCP90", 100, 10, Some((36, 27, 41, 60)), 0x846d509e01157858),
+    ("collisionBCP90", 150, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionBCP90", 200, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionBDP90", 0, 10, Some((11, 2, 16, 85)), 0x80f7b353ae285957),
+    ("collisionBDP90", 100, 10, Some((36, 27, 41, 60)), 0xdca3067f716c3a05),
+    ("collisionBDP90", 150, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionBDP90", 200, 8, Some((47, 38, 48, 51)), 0x8e80a44028b995d9),
+    ("collisionCCP90", 0, 10, Some((11, 2, 16, 85)), 0x3761ca13e66ba47f),
+    ("collisionCCP90", 100, 10, Some((36, 27, 41, 60)), 0x2accdfb8c5734411),
+    ("collisionCCP90", 150, 8, Some((47, 39, 48, 51)), 0x6451fd0d8c31369d),
+    ("collisionCCP90", 200, 8, Some((47, 39, 48, 51)), 0x6451fd0d8c31369d),
+    ("collisionCDP90", 0, 10, Some((11, 2, 16, 85)), 0x2694ad48b2475de4),
+    ("collisionCDP90", 100, 10, Some((36, 27, 41, 60)), 0x86456ea5e878decc),
+    ("collisionCDP90", 150, 8, Some((47, 39, 48, 51)), 0x6451fd0d8c31369d),
+    ("collisionCDP90", 200, 8, Some((47, 39, 48, 51)), 0x6451fd0d8c31369d),
+    ("collisionDDP90", 0, 10, Some((11, 2, 16, 85)), 0x4333aada63d84b35),
+    ("collisionDDP90", 100, 10, Some((36, 27, 41, 60)), 0xd92c17cb5a83fd2f),
+    ("collisionDDP90", 150, 8, Some((47, 39, 48, 51)), 0x6451fd0d8c31369d),
+    ("collisionDDP90", 200, 8, Some((47, 39, 48, 51)), 0x6451fd0d8c31369d),
+    ("100collisionAA", 0, 1000, Some((1, 2, 293, 294)), 0xf1d6a445d85a8f3d),
+    ("100collisionAA", 200, 1000, Some((51, 52, 243, 244)), 0x3b2e820a74939f15),
+    ("100collisionAA", 400, 550, Some((100, 101, 194, 195)), 0x5948aa90e11297a9),
+    ("100collisionAA", 600, 0, None, 0xcbf29ce484222325),
+    ("100collisionAC", 0, 1000, Some((1, 2, 294, 294)), 0x608c7e1f8366a251),
+    ("100collisionAC", 200, 1000, Some((51, 52, 244, 244)), 0xe046485d794168a1),
+    ("100collisionAC", 400, 895, Some((98, 102, 194, 194)), 0x339e7147c0478fee),
+    ("100collisionAC", 600, 458, Some((69, 97, 204, 200)), 0x7db811c5836e8ede),
+    ("100collisionAH1", 0, 1000, Some((1, 2, 295, 294)), 0x84bf8797bd2b1835),
+    ("100collisionAH1", 200, 1000, Some((51, 52, 295, 294)), 0xd5b763da3fb14a45),
+    ("100collisionAH1", 400, 1000, Some((101, 102, 295, 294)), 0x31b8555388020825),
+    ("100collisionAH1", 600, 931, Some((151, 152, 295, 294)), 0xfcf4e7ecf056d4ae),
+    ("100collisionAH", 0, 1000, Some((1, 2, 294, 294)), 0x5c6c37446c498c79),
+    ("100collisionAH", 200, 1000, Some((51, 52, 294, 294)), 0xa48b33ee9782d6c9),
+    ("100collisionAH", 400, 1000, Some((101, 102, 294, 294)), 0xc3f8a4edd561b629),
+    ("100collisionAH", 600, 899, Some((151, 152, 294, 294)), 0x28e1371307aa01d9),
+];
+
+/// FNV-1a hash of the live cells in row order, stable across platforms and releases
+fn live_cells_hash(engine: &dyn LifeEngine) -> u64 {
+    let mut cells = engine.live_cells().collect::<Vec<_>>();
+    cells.sort_by_key(|&(pos, state)| (pos.y, pos.x, state));
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (pos, state) in cells {
+        let bytes = (pos.x as u64)
+            .to_le_bytes()
+            .into_iter()
+            .chain((pos.y as u64).to_le_bytes())
+            .chain([state]);
+        for byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+#[test]
+fn every_library_pattern_has_golden_outputs() {
+    for (name, _) in PATTERNS.iter() {
+        assert!(
+            GOLDEN.iter().any(|(golden_name, ..)| golden_name == name),
+            "no golden outputs for {}",
+            name
+        );
+    }
+}
+
+#[test]
+fn library_patterns_match_golden_outputs() {
+    for (name, _) in PATTERNS.iter() {
+        let pattern = Pattern::named(name).unwrap();
+        let mut grid = Grid::with_rule(
+            pattern.width() + MARGIN,
+            pattern.height() + MARGIN,
+            Rule::conway(),
+        );
+        grid.set_states(pattern.cells()).unwrap();
+        for backend in Backend::ALL {
+            let mut engine = backend.engine(grid.clone()).unwrap();
+            let cases = GOLDEN
+                .iter()
+                .filter(|(golden_name, ..)| golden_name == name);
+            for &(_, generation, population, bounding_box, hash) in cases {
+                engine.step_n(generation - engine.generation());
+                let found = engine
+                    .bounding_box()
+                    .map(|(min, max)| (min.x, min.y, max.x, max.y));
+                assert_eq!(
+                    engine.population(),
+                    population,
+                    "{} population in generation {} on the {} backend",
+                    name,
+                    generation,
+                    backend
+                );
+                assert_eq!(
+                    found, bounding_box,
+                    "{} bounding box in generation {} on the {} backend",
+                    name, generation, backend
+                );
+                assert_eq!(
+                    live_cells_hash(engine.as_ref()),
+                    hash,
+                    "{} cells in generation {} on the {} backend",
+                    name,
+                    generation,
+                    backend
+                );
+            }
+        }
+    }
+}
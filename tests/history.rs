@@ -0,0 +1,120 @@
+//! Checks the history brings a grid back to every state it recorded, across keyframes and
+//! after the oldest states are dropped, and that it records every generation of a background
+//! simulation.
+
+use game_of_life::history::History;
+use game_of_life::simulation::{Simulation, Speed};
+use game_of_life::{Cell, Grid, Pattern, Rule};
+use std::mem::size_of;
+use std::thread;
+use std::time::Duration;
+
+/// Generations recorded, spanning a few keyframes
+const GENERATIONS: u64 = 200;
+/// Side of the grid, large enough for the glider guns to run without reaching the edge
+const SIDE: usize = 160;
+
+/// Glider gun grid and every state it goes through, starting with the first one
+fn run() -> (Grid, Vec<Vec<Cell>>) {
+    let gun = Pattern::named("glider-gun").unwrap();
+    let mut grid = Grid::with_rule(SIDE, SIDE, Rule::conway());
+    grid.set_states(gun.cells()).unwrap();
+    let mut states = vec![grid.cells().to_vec()];
+    let mut probe = grid.clone();
+    for _ in 0..GENERATIONS {
+        probe.step();
+        states.push(probe.cells().to_vec());
+    }
+    (grid, states)
+}
+
+/// Fails unless `grid` is in the state of `generation`
+fn assert_at(grid: &Grid, states: &[Vec<Cell>], generation: u64) {
+    assert_eq!(grid.generation(), generation);
+    assert!(
+        grid.cells() == states[generation as usize],
+        "cells differ from those of generation {}",
+        generation
+    );
+}
+
+#[test]
+fn undo_redo_and_go_to_round_trip_across_keyframes() {
+    let (mut grid, states) = run();
+    let mut history = History::new(&grid, usize::MAX);
+    for _ in 0..GENERATIONS {
+        grid.step();
+        history.record(&grid);
+    }
+    assert_eq!(history.frame_count(), GENERATIONS as usize + 1);
+    for generation in (0..GENERATIONS).rev() {
+        assert!(history.undo(&mut grid));
+        assert_at(&grid, &states, generation);
+    }
+    assert!(!history.undo(&mut grid));
+    for generation in 1..=GENERATIONS {
+        assert!(history.redo(&mut grid));
+        assert_at(&grid, &states, generation);
+    }
+    assert!(!history.redo(&mut grid));
+    // Jumps landing on both sides of keyframes, back and forth
+    for position in [63, 64, 65, 0, 127, 128, 129, 191, 200, 1] {
+        history.go_to(position, &mut grid);
+        assert_at(&grid, &states, position as u64);
+    }
+}
+
+#[test]
+fn dropped_states_leave_the_rest_intact() {
+    let (mut grid, states) = run();
+    // Room for three keyframes, but not with all the diffs between them, so the last two
+    // keyframes and their diffs are kept
+    let cap = 3 * SIDE * SIDE * size_of::<Cell>();
+    let mut history = History::new(&grid, cap);
+    for _ in 0..GENERATIONS {
+        grid.step();
+        history.record(&grid);
+    }
+    let (oldest, newest) = history.generations();
+    assert!(oldest > 0, "no state was dropped");
+    assert_eq!(newest, GENERATIONS);
+    assert_eq!(history.frame_count() as u64, newest - oldest + 1);
+    history.go_to(0, &mut grid);
+    assert_at(&grid, &states, oldest);
+    while history.redo(&mut grid) {
+        assert_at(&grid, &states, oldest + history.position() as u64);
+    }
+    assert_at(&grid, &states, GENERATIONS);
+    // Going back and moving on from there drops the later states
+    history.go_to(history.frame_count() - 10, &mut grid);
+    grid.step();
+    history.record(&grid);
+    assert_eq!(history.generations().1, GENERATIONS - 8);
+    assert!(!history.redo(&mut grid));
+    assert!(history.undo(&mut grid));
+    assert_at(&grid, &states, GENERATIONS - 9);
+}
+
+#[test]
+fn background_simulation_records_every_generation() {
+    let (mut grid, states) = run();
+    let mut history = History::new(&grid, usize::MAX);
+    let mut simulation = Simulation::start(Box::new(grid.clone()));
+    simulation.restore(&grid);
+    simulation.run(Speed::Max);
+    // Taken a few generations at a time, as a slow frame would
+    while history.generations().1 < GENERATIONS {
+        thread::sleep(Duration::from_millis(1));
+        for step in simulation.take_steps() {
+            history.record_changes(step.generation, step.rng_position, step.changes);
+        }
+    }
+    simulation.pause();
+    let newest = history.generations().1;
+    assert_eq!(history.frame_count() as u64, newest + 1);
+    history.go_to(0, &mut grid);
+    for generation in 1..=GENERATIONS {
+        assert!(history.redo(&mut grid));
+        assert_at(&grid, &states, generation);
+    }
+}
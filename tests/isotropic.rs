@@ -0,0 +1,139 @@
+//! Pins the Hensel letters of isotropic rules to the neighbour configurations Golly gives
+//! them, and checks a rule written with them.
+
+use game_of_life::isotropic::IsotropicRule;
+use game_of_life::{Grid, Point, Rule};
+
+/// Bit of the cell itself in a configuration, laid out row by row from the north-west
+/// neighbour
+const CENTRE: usize = 16;
+/// Every neighbour of a configuration, without the cell itself
+const NEIGHBOURS: usize = 511 & !CENTRE;
+
+/// Count, letter and one configuration of every Hensel letter up to 4 neighbours. Counts above
+/// 4 use the complements of those of `8 - count`
+#[rustfmt::skip]
+const LETTERS: &[(usize, char, usize)] = &[
+    (1, 'c', 4),   // NE
+    (1, 'e', 2),   // N
+    (2, 'c', 260), // NE, SE
+    (2, 'e', 34),  // N, E
+    (2, 'a', 6),   // N, NE
+    (2, 'i', 130), // N, S
+    (2, 'k', 258), // N, SE
+    (2, 'n', 68),  // NE, SW
+    (3, 'c', 324), // NE, SW, SE
+    (3, 'e', 162), // N, E, S
+    (3, 'a', 38),  // N, NE, E
+    (3, 'i', 7),   // NW, N, NE
+    (3, 'k', 98),  // N, E, SW
+    (3, 'y', 322), // N, SW, SE
+    (3, 'n', 262), // N, NE, SE
+    (3, 'q', 70),  // N, NE, SW
+    (3, 'j', 14),  // N, NE, W
+    (3, 'r', 134), // N, NE, S
+    (4, 'c', 325), // NW, NE, SW, SE
+    (4, 'e', 170), // N, W, E, S
+    (4, 'a', 294), // N, NE, E, SE
+    (4, 'i', 390), // N, NE, S, SE
+    (4, 'k', 270), // N, NE, W, SE
+    (4, 'y', 326), // N, NE, SW, SE
+    (4, 'n', 263), // NW, N, NE, SE
+    (4, 'q', 102), // N, NE, E, SW
+    (4, 'j', 142), // N, NE, W, S
+    (4, 'r', 166), // N, NE, E, S
+    (4, 't', 135), // NW, N, NE, S
+    (4, 'w', 78),  // N, NE, W, SW
+    (4, 'z', 198), // N, NE, SW, S
+];
+
+#[test]
+fn letters_match_golly() {
+    for &(count, letter, _) in LETTERS {
+        // The letter itself, and the one of the complement for counts above 4
+        let mut cases = vec![(count, false)];
+        if count < 4 {
+            cases.push((8 - count, true));
+        }
+        for (count, complement) in cases {
+            let birth = format!("B{}{}/S", count, letter)
+                .parse::<IsotropicRule>()
+                .unwrap();
+            let survival = format!("B/S{}{}", count, letter)
+                .parse::<IsotropicRule>()
+                .unwrap();
+            for &(other_count, other_letter, config) in LETTERS {
+                if other_count != count.min(8 - count) {
+                    continue;
+                }
+                let config = if complement {
+                    !config & NEIGHBOURS
+                } else {
+                    config
+                };
+                let expected = other_letter == letter;
+                assert_eq!(
+                    birth.next_state(config),
+                    expected,
+                    "B{}{} on the configuration of {}{}",
+                    count,
+                    letter,
+                    count,
+                    other_letter
+                );
+                assert_eq!(
+                    survival.next_state(config | CENTRE),
+                    expected,
+                    "S{}{} on the configuration of {}{}",
+                    count,
+                    letter,
+                    count,
+                    other_letter
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn tlife_kills_blinkers() {
+    let tlife = "B3/S2-i34q".parse::<Rule>().unwrap();
+    let mut grid = Grid::with_rule(5, 5, tlife);
+    let row = (1..=3).map(|x| Point { x, y: 2 }).collect::<Vec<Point>>();
+    grid.set_state(&row).unwrap();
+    // The middle cell has its two neighbours across it, 2i, and dies, while the cells above and
+    // below it see a row of three, 3i, and are born
+    grid.step();
+    assert_eq!(
+        grid.live_cells()
+            .map(|(pos, _)| pos)
+            .collect::<Vec<Point>>(),
+        [Point { x: 2, y: 1 }, Point { x: 2, y: 3 }]
+    );
+    grid.step();
+    assert_eq!(grid.live_cells().count(), 0);
+}
+
+#[test]
+fn tlife_keeps_only_4q_among_4_neighbours() {
+    let tlife = "B3/S2-i34q".parse::<Rule>().unwrap();
+    for &(count, letter, config) in LETTERS.iter().filter(|&&(count, ..)| count == 4) {
+        let mut grid = Grid::with_rule(3, 3, tlife.clone());
+        let cells = (0..9)
+            .filter(|bit| (config | CENTRE) & (1 << bit) != 0)
+            .map(|bit| Point {
+                x: bit % 3,
+                y: bit / 3,
+            })
+            .collect::<Vec<Point>>();
+        grid.set_state(&cells).unwrap();
+        grid.step();
+        assert_eq!(
+            grid.cell(Point { x: 1, y: 1 }).unwrap().is_alive(),
+            letter == 'q',
+            "cell with {}{} neighbours",
+            count,
+            letter
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Checks Larger than Life rules, whose neighbours are counted with a summed-area table,
+//! against counting them one by one.
+
+use game_of_life::ltl::{LtlNeighbourhood, LtlRule};
+use game_of_life::{Grid, Point, Rule};
+
+/// Generations every starting state is run for
+const GENERATIONS: u64 = 12;
+
+/// Live cells of the next generation of `grid`, counting the neighbours of every cell one by
+/// one, cells outside the grid being dead
+fn naive_step(grid: &Grid, rule: &LtlRule) -> Vec<bool> {
+    let range = rule.range() as isize;
+    let alive = |x: isize, y: isize| {
+        x >= 0
+            && y >= 0
+            && grid
+                .cell(Point {
+                    x: x as usize,
+                    y: y as usize,
+                })
+                .is_some_and(|cell| cell.is_alive())
+    };
+    (0..grid.height() as isize)
+        .flat_map(|y| (0..grid.width() as isize).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let count = (-range..=range)
+                .flat_map(|y_off| (-range..=range).map(move |x_off| (x_off, y_off)))
+                .filter(|&(x_off, y_off)| match rule.neighbourhood() {
+                    LtlNeighbourhood::Moore => true,
+                    LtlNeighbourhood::VonNeumann => x_off.abs() + y_off.abs() <= range,
+                })
+                .filter(|&offset| offset != (0, 0) || rule.includes_middle())
+                .filter(|&(x_off, y_off)| alive(x + x_off, y + y_off))
+                .count();
+            rule.next_state(alive(x, y), count)
+        })
+        .collect()
+}
+
+#[test]
+fn summed_area_counts_match_naive_counts() {
+    let rules = [
+        // Bosco's Rule
+        "R5,C0,M1,S34..58,B34..45,NM",
+        "R2,C0,M0,S3..8,B5..7,NM",
+        "R3,C0,M1,S2..6,B3..5,NN",
+        "R4,C0,M0,S5..12,B6..9,NN",
+    ];
+    for rulestring in rules {
+        let rule = rulestring.parse::<LtlRule>().unwrap();
+        for seed in 0..2 {
+            // Wider than the range on both sides, and narrower than it near the edges
+            let mut grid = Grid::with_rule(37, 29, Rule::LargerThanLife(rule.clone()))
+                .with_random_soup(0.35, seed);
+            for generation in 1..=GENERATIONS {
+                let expected = naive_step(&grid, &rule);
+                grid.step();
+                let alive = grid
+                    .cells()
+                    .iter()
+                    .map(|cell| cell.is_alive())
+                    .collect::<Vec<bool>>();
+                assert!(
+                    alive == expected,
+                    "{} soup {}: summed-area counts diverge in generation {}",
+                    rulestring,
+                    seed,
+                    generation
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn range_1_moore_rule_is_conway() {
+    let ltl = "R1,C0,M0,S2..3,B3..3,NM".parse::<Rule>().unwrap();
+    let mut grid = Grid::with_rule(40, 30, ltl).with_random_soup(0.35, 0);
+    let mut life = Grid::with_rule(40, 30, Rule::conway());
+    life.set_states(&grid.live_cells().collect::<Vec<_>>())
+        .unwrap();
+    for generation in 1..=GENERATIONS {
+        grid.step();
+        life.step();
+        assert!(
+            grid.live_cells().eq(life.live_cells()),
+            "R1 Moore rule and B3/S23 diverge in generation {}",
+            generation
+        );
+    }
+}
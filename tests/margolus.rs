@@ -0,0 +1,53 @@
+//! Checks the conservation and reversibility properties the Margolus presets are known for.
+
+use game_of_life::margolus::MargolusRule;
+use game_of_life::{Grid, Rule};
+
+/// Generations every starting state is run for
+const GENERATIONS: u64 = 40;
+
+#[test]
+fn billiard_balls_are_conserved() {
+    for seed in 0..4 {
+        let mut grid =
+            Grid::with_rule(40, 30, "BBM".parse::<Rule>().unwrap()).with_random_soup(0.2, seed);
+        let population = grid.live_cells().count();
+        for generation in 1..=GENERATIONS {
+            grid.step();
+            assert_eq!(
+                grid.live_cells().count(),
+                population,
+                "soup {} changes population in generation {}",
+                seed,
+                generation
+            );
+        }
+    }
+}
+
+#[test]
+fn critters_runs_backwards() {
+    let critters = MargolusRule::critters();
+    // Critters replaces each block configuration by a different one, so the table can be
+    // turned around
+    let mut inverse = [0; 16];
+    for block in 0..16 {
+        inverse[critters.next_block(block) as usize] = block;
+    }
+    for seed in 0..4 {
+        let start =
+            Grid::with_rule(41, 29, Rule::Margolus(critters.clone())).with_random_soup(0.3, seed);
+        let mut grid = start.clone();
+        grid.step_n(GENERATIONS);
+        // Stepping from a generation of the same parity as the last one stepped, so each
+        // generation of the reverse run uses the partition of the one it undoes
+        let mut reverse = Grid::with_rule(41, 29, Rule::Margolus(MargolusRule::new(inverse)));
+        reverse.restore(grid.cells().to_vec(), GENERATIONS - 1, 0);
+        reverse.step_n(GENERATIONS);
+        assert!(
+            reverse.live_cells().eq(start.live_cells()),
+            "soup {} doesn't come back to its starting state",
+            seed
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Checks grids large enough to be updated in parallel row tiles against updates known to give
+//! the same generations, for the rules and schemes that go through them.
+
+use game_of_life::engine::Backend;
+use game_of_life::scheme::UpdateScheme;
+use game_of_life::{Grid, Point, Rule};
+
+/// Generations every starting state is run for
+const GENERATIONS: u64 = 30;
+/// Size of the grids, with more cells than the grids updated on a single thread
+const SIZE: (usize, usize) = (160, 120);
+
+/// Soup of `rule` filling a grid of `SIZE`
+fn soup(rule: Rule, seed: u64) -> Grid {
+    Grid::with_rule(SIZE.0, SIZE.1, rule).with_random_soup(0.35, seed)
+}
+
+#[test]
+fn larger_than_life_matches_sparse_conway() {
+    // Range 1 Moore neighbourhood without the cell itself, which is B3/S23
+    let ltl = "R1,C0,M0,S2..3,B3..3,NM".parse::<Rule>().unwrap();
+    let mut grid = soup(ltl, 0);
+    let mut conway = Grid::with_rule(SIZE.0, SIZE.1, Rule::conway());
+    conway
+        .set_states(&grid.live_cells().collect::<Vec<_>>())
+        .unwrap();
+    let mut sparse = Backend::Sparse.engine(conway).unwrap();
+    for generation in 1..=GENERATIONS {
+        grid.step();
+        sparse.step();
+        // The sparse backend lists its cells in no particular order
+        let mut live_cells = sparse.live_cells().collect::<Vec<(Point, u8)>>();
+        live_cells.sort_by_key(|&(pos, _)| (pos.y, pos.x));
+        assert!(
+            grid.live_cells().eq(live_cells),
+            "diverges from the sparse backend in generation {}",
+            generation
+        );
+    }
+}
+
+#[test]
+fn asynchronous_updates_of_every_cell_are_synchronous() {
+    // With every cell updated, the scheme only changes the way the grid is updated
+    let every_cell = UpdateScheme::Asynchronous { alpha: 1.0 };
+    for rulestring in ["B3/S23", "B2-a/S12", "WireWorld"] {
+        let rule = rulestring.parse::<Rule>().unwrap();
+        let mut synchronous = soup(rule, 1);
+        let mut asynchronous = synchronous.clone().with_scheme(every_cell, 0).unwrap();
+        for generation in 1..=GENERATIONS {
+            synchronous.step();
+            asynchronous.step();
+            assert!(
+                asynchronous.cells() == synchronous.cells(),
+                "{}: diverges from synchronous updates in generation {}",
+                rulestring,
+                generation
+            );
+        }
+    }
+}
+
+#[test]
+fn margolus_blocks_match_a_small_grid() {
+    // Cells only see their own block, so changes spread a cell per generation at most and the
+    // cells further than `GENERATIONS` from the edge of the small grid can't tell the grids
+    // apart
+    let side = 96;
+    let same = side - GENERATIONS as usize;
+    for rulestring in ["Critters", "BBM"] {
+        let rule = rulestring.parse::<Rule>().unwrap();
+        let mut small = Grid::with_rule(side, side, rule.clone()).with_random_soup(0.3, 2);
+        let mut large = Grid::with_rule(SIZE.0, SIZE.1, rule);
+        large
+            .set_states(&small.live_cells().collect::<Vec<_>>())
+            .unwrap();
+        for generation in 1..=GENERATIONS {
+            small.step();
+            large.step();
+            for (x, y) in (0..same).flat_map(|y| (0..same).map(move |x| (x, y))) {
+                let pos = Point { x, y };
+                assert_eq!(
+                    large.cell(pos).unwrap().state(),
+                    small.cell(pos).unwrap().state(),
+                    "{}: cell ({}, {}) differs in generation {}",
+                    rulestring,
+                    x,
+                    y,
+                    generation
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Checks RLE patterns round trip, and oversized ones or ones in states the rule lacks are
+//! rejected rather than built.
+
+use game_of_life::engine::Backend;
+use game_of_life::{Grid, Pattern, Point, Rule};
+
+#[test]
+fn rle_round_trips() {
+    let glider = Pattern::named("gliderA").unwrap().trimmed();
+    let parsed = glider.to_rle(&Rule::conway()).parse::<Pattern>().unwrap();
+    assert_eq!(parsed, glider);
+}
+
+#[test]
+fn oversized_runs_are_rejected() {
+    let oversized = [
+        // A count that doesn't fit in a number
+        "99999999999999999999999o!",
+        // A count that fits, but makes billions of cells
+        "4000000000o!",
+        // Runs past the declared size
+        "x = 3, y = 3\n4o!",
+        "x = 3, y = 3\n2b2o!",
+        "x = 3, y = 3\n3$o!",
+        // A declared size too large to build
+        "x = 4000000000, y = 1\n4000000000o!",
+    ];
+    for rle in oversized {
+        assert!(
+            rle.parse::<Pattern>().is_err(),
+            "{:?} should not parse",
+            rle
+        );
+    }
+    assert!("x = 3, y = 3\nbo$2bo$3o!".parse::<Pattern>().is_ok());
+}
+
+#[test]
+fn states_outside_the_rule_are_rejected() {
+    // An electron head, then a state WireWorld doesn't have
+    let pattern = "x = 2, y = 1\nAD!".parse::<Pattern>().unwrap();
+    let wireworld = "WireWorld".parse::<Rule>().unwrap();
+    let mut grid = Grid::with_rule(4, 4, wireworld.clone());
+    assert!(grid
+        .set_cells(&pattern.placed_at(Point { x: 1, y: 1 }))
+        .is_err());
+    assert!(grid.set_states(pattern.cells()).is_err());
+    assert_eq!(grid.live_cells().count(), 0, "nothing is placed");
+    assert!(wireworld.check_state(3).is_ok());
+    assert!(wireworld.check_state(4).is_err());
+    for backend in Backend::ALL {
+        let mut engine = backend
+            .engine(Grid::with_rule(4, 4, Rule::conway()))
+            .unwrap();
+        assert!(
+            engine
+                .set_cells(&[(Point { x: 0, y: 0 }, 1), (Point { x: 1, y: 0 }, 2)])
+                .is_err(),
+            "{} backend takes state 2 under B3/S23",
+            backend
+        );
+        assert_eq!(engine.population(), 0, "{} backend places cells", backend);
+    }
+}
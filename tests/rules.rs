@@ -0,0 +1,60 @@
+//! Checks rules that can't be used are rejected with an error rather than a panic.
+
+use game_of_life::scheme::UpdateScheme;
+use game_of_life::table::RuleTable;
+use game_of_life::{Grid, Rule};
+
+#[test]
+fn isotropic_rules_need_both_halves() {
+    for rulestring in ["/S2-a", "S2-a/", "B2-a/", "/B2-a"] {
+        assert!(
+            rulestring.parse::<Rule>().is_err(),
+            "'{}' should not parse",
+            rulestring
+        );
+    }
+    assert!("B2-a/S12".parse::<Rule>().is_ok());
+    assert!("S12/B2-a".parse::<Rule>().is_ok());
+}
+
+#[test]
+fn tables_need_between_2_and_255_states() {
+    let table = |states: &str| {
+        format!(
+            "@RULE Flip\n@TABLE\nn_states:{}\nneighborhood:vonNeumann\nsymmetries:none\n0,1,0,0,0,1\n",
+            states
+        )
+    };
+    for states in ["0", "1", "256", "-1", ""] {
+        assert!(
+            table(states).parse::<RuleTable>().is_err(),
+            "n_states:{} should not parse",
+            states
+        );
+    }
+    for states in ["2", "255"] {
+        assert!(
+            table(states).parse::<RuleTable>().is_ok(),
+            "n_states:{} should parse",
+            states
+        );
+    }
+}
+
+#[test]
+fn margolus_rules_only_update_synchronously() {
+    let rule = "BBM".parse::<Rule>().unwrap();
+    for scheme in ["random-order", "async:0.5", "probabilistic:0.5,0.5"] {
+        let scheme = scheme.parse::<UpdateScheme>().unwrap();
+        assert!(
+            Grid::with_rule(8, 8, rule.clone())
+                .with_scheme(scheme, 0)
+                .is_err(),
+            "{} scheme should be rejected",
+            scheme
+        );
+    }
+    assert!(Grid::with_rule(8, 8, rule)
+        .with_scheme(UpdateScheme::Synchronous, 0)
+        .is_ok());
+}
@@ -0,0 +1,63 @@
+//! Checks runs under random update schemes are reproducible from their seed.
+
+use game_of_life::scheme::UpdateScheme;
+use game_of_life::{Grid, Rule};
+
+/// Generations every starting state is run for
+const GENERATIONS: u64 = 24;
+/// Every scheme drawing random numbers
+const SCHEMES: [&str; 3] = ["random-order", "async:0.5", "probabilistic:0.8,0.9"];
+
+/// Soup under B3/S23 updated with `scheme`, drawing random numbers from `seed`
+fn soup(scheme: &str, seed: u64) -> Grid {
+    Grid::with_rule(48, 40, Rule::conway())
+        .with_random_soup(0.35, 0)
+        .with_scheme(scheme.parse::<UpdateScheme>().unwrap(), seed)
+        .unwrap()
+}
+
+#[test]
+fn runs_repeat_from_their_seed() {
+    for scheme in SCHEMES {
+        let mut first = soup(scheme, 7);
+        let mut second = soup(scheme, 7);
+        let mut other_seed = soup(scheme, 8);
+        first.step_n(GENERATIONS);
+        second.step_n(GENERATIONS);
+        other_seed.step_n(GENERATIONS);
+        assert!(
+            first.cells() == second.cells(),
+            "{} runs differ with the same seed",
+            scheme
+        );
+        assert!(
+            first.cells() != other_seed.cells(),
+            "{} runs are the same with another seed",
+            scheme
+        );
+    }
+}
+
+#[test]
+fn runs_resume_from_their_rng_position() {
+    for scheme in SCHEMES {
+        let mut grid = soup(scheme, 7);
+        grid.step_n(GENERATIONS / 2);
+        let (cells, generation, rng_position) = (
+            grid.cells().to_vec(),
+            grid.generation(),
+            grid.rng_position(),
+        );
+        grid.step_n(GENERATIONS / 2);
+        // Restored into a grid that went on in another way
+        let mut resumed = soup(scheme, 7);
+        resumed.step_n(3);
+        resumed.restore(cells, generation, rng_position);
+        resumed.step_n(GENERATIONS / 2);
+        assert!(
+            resumed.cells() == grid.cells(),
+            "{} run differs after resuming",
+            scheme
+        );
+    }
+}
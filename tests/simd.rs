@@ -0,0 +1,78 @@
+//! Checks the wide adders and the life-like update built on them against their scalar
+//! counterparts.
+
+mod common;
+
+use common::{random_soup, RULES};
+use game_of_life::neighbourhood::Neighbourhood;
+use game_of_life::rule::LifeRule;
+use game_of_life::{simd, Grid, Rule};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Generations every starting state is run for
+const GENERATIONS: u64 = 48;
+
+/// Steps `grid` with and without wide adders in lockstep, failing at the first generation in
+/// which they differ
+fn assert_matches_scalar(name: &str, grid: Grid) {
+    let mut wide = grid.clone().with_simd(true);
+    let mut scalar = grid.with_simd(false);
+    for generation in 1..=GENERATIONS {
+        wide.step();
+        scalar.step();
+        assert!(
+            wide.cells() == scalar.cells(),
+            "{}: wide adders diverge from counting cell by cell in generation {}",
+            name,
+            generation
+        );
+    }
+}
+
+#[test]
+fn add_matches_scalar() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    // Lengths around the widths of the adders, to cover the lanes left over by them
+    for len in [0, 1, 15, 16, 17, 31, 32, 33, 100, 1000] {
+        let counts = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let addend = (0..len).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let mut wide = counts.clone();
+        simd::add(&mut wide, &addend);
+        let mut scalar = counts;
+        simd::add_scalar(&mut scalar, &addend);
+        assert_eq!(wide, scalar, "sums differ for {} lanes", len);
+    }
+}
+
+#[test]
+fn add_stops_at_the_shorter_slice() {
+    let mut counts = vec![1; 40];
+    simd::add(&mut counts, &[2; 35]);
+    assert_eq!(&counts[..35], &[3; 35][..]);
+    assert_eq!(&counts[35..], &[1; 5][..]);
+}
+
+#[test]
+fn life_update_matches_scalar() {
+    for rulestring in RULES {
+        let rule = rulestring.parse::<Rule>().unwrap();
+        for seed in 0..2 {
+            // Sizes that aren't a whole number of tiles
+            let name = format!("{} soup {}", rulestring, seed);
+            assert_matches_scalar(&name, random_soup(70, 45, rule.clone(), seed));
+        }
+    }
+}
+
+#[test]
+fn wide_neighbourhoods_match_scalar() {
+    for mask in [
+        "10101/00000/10001/00000/10101",
+        "1111111/1000001/1000001/1000001/1111111",
+    ] {
+        let neighbourhood = mask.parse::<Neighbourhood>().unwrap();
+        let rule = Rule::Life(LifeRule::parse_for("B3/S23", neighbourhood).unwrap());
+        assert_matches_scalar(mask, random_soup(50, 37, rule, 0));
+    }
+}
@@ -0,0 +1,57 @@
+//! Checks snapshots round trip and corrupt ones are rejected before any cells are built.
+
+use game_of_life::snapshot::{from_snapshot, to_snapshot};
+use game_of_life::{Grid, Point, Rule};
+
+/// Snapshot of a 4 by 2 grid at generation 3 with the given rows of cells
+fn snapshot(rows: &str) -> String {
+    format!(
+        "#GameOfLife snapshot 1\nwidth 4\nheight 2\ngeneration 3\nrule B3/S23\ncells\n{}\n",
+        rows
+    )
+}
+
+#[test]
+fn snapshots_round_trip() {
+    let mut grid = Grid::with_rule(6, 5, Rule::conway());
+    grid.set_state(&[
+        Point { x: 1, y: 2 },
+        Point { x: 2, y: 2 },
+        Point { x: 3, y: 2 },
+    ])
+    .unwrap();
+    grid.step_n(3);
+    let loaded = from_snapshot(&to_snapshot(&grid)).unwrap();
+    assert_eq!(loaded.generation(), 3);
+    assert!(loaded.cells() == grid.cells());
+}
+
+#[test]
+fn corrupt_snapshots_are_rejected() {
+    assert!(from_snapshot(&snapshot("4*0\n2*0 1@2 0")).is_ok());
+    let corrupt = [
+        // A run far past the width of the grid
+        "4*0\n99999999999999*0",
+        // More cells than the width
+        "4*0\n3*0 2*1@2",
+        // A state the rule doesn't have
+        "4*0\n3*0 2@2",
+        // A cell changing after the generation of the snapshot
+        "4*0\n3*0 1@4",
+        // A missing row
+        "4*0",
+    ];
+    for rows in corrupt {
+        assert!(
+            from_snapshot(&snapshot(rows)).is_err(),
+            "rows {:?} should not load",
+            rows
+        );
+    }
+    // Sizes that would take too much memory, rejected before any row is read
+    for size in ["width 100000\nheight 100000", "width 4096\nheight 8192"] {
+        let header = snapshot("").replace("width 4\nheight 2", size);
+        let err = from_snapshot(&header).unwrap_err().to_string();
+        assert!(err.contains("larger than"), "{:?} fails with {}", size, err);
+    }
+}
@@ -0,0 +1,103 @@
+//! Checks rule tables: the built-in WireWorld table, how symmetries expand transitions, and
+//! that tables too large for a lookup table behave like those that have one.
+
+use game_of_life::table::RuleTable;
+use game_of_life::{Grid, Pattern, Point, Rule};
+
+/// Period of the WireWorld clock, one electron going round its loop of 14 cells
+const CLOCK_PERIOD: u64 = 14;
+
+/// Von Neumann table turning on a dead cell whose north and east neighbours are on, the
+/// transition also applying under `symmetries`
+fn corner_table(symmetries: &str) -> RuleTable {
+    format!(
+        "@RULE Corner\n@TABLE\nn_states:2\nneighborhood:vonNeumann\nsymmetries:{}\n0,1,1,0,0,1\n",
+        symmetries
+    )
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn wireworld_clock_pulses_every_14_generations() {
+    let clock = Pattern::named("wireworld-clock").unwrap();
+    let mut grid = Grid::with_rule(48, 16, Rule::Table(RuleTable::wireworld()));
+    grid.set_states(clock.cells()).unwrap();
+    // Generations in which an electron head is on the wire leaving the loop
+    let wire = Point { x: 30, y: 11 };
+    let mut heads = vec![];
+    for _ in 0..6 * CLOCK_PERIOD {
+        grid.step();
+        if grid.cell(wire).unwrap().state() == 1 {
+            heads.push(grid.generation());
+        }
+    }
+    assert!(
+        heads.len() >= 4,
+        "only {} pulses reached the wire",
+        heads.len()
+    );
+    for pair in heads.windows(2) {
+        assert_eq!(pair[1] - pair[0], CLOCK_PERIOD, "pulses at {:?}", heads);
+    }
+}
+
+#[test]
+fn symmetries_expand_transitions() {
+    // Neighbours in the order of the table: N, E, S, W
+    let pairs = [
+        ("north and east", [1, 1, 0, 0]),
+        ("east and south", [0, 1, 1, 0]),
+        ("south and west", [0, 0, 1, 1]),
+        ("west and north", [1, 0, 0, 1]),
+        ("north and south", [1, 0, 1, 0]),
+        ("east and west", [0, 1, 0, 1]),
+    ];
+    let cases = [
+        ("none", 1),
+        ("rotate4", 4),
+        ("rotate4reflect", 4),
+        // Opposite neighbours too, as any order of them matches
+        ("permute", 6),
+    ];
+    for (symmetries, born) in cases {
+        let table = corner_table(symmetries);
+        for (idx, (name, neighbours)) in pairs.iter().enumerate() {
+            let mut states = vec![0];
+            states.extend(neighbours);
+            assert_eq!(
+                table.next_state(&states),
+                u8::from(idx < born),
+                "symmetries:{} with the {} neighbours on",
+                symmetries,
+                name
+            );
+        }
+    }
+}
+
+#[test]
+fn tables_without_lookup_match_those_with_one() {
+    let lookup = RuleTable::wireworld();
+    // A fifth state takes the Moore neighbourhood past the configurations turned into a
+    // lookup table, so transitions are evaluated cell by cell
+    let evaluated = lookup
+        .source()
+        .replace("n_states:4", "n_states:5")
+        .parse::<RuleTable>()
+        .unwrap();
+    let mut states = [0; 9];
+    for config in 0..4usize.pow(9) {
+        let mut rest = config;
+        for state in states.iter_mut() {
+            *state = (rest % 4) as u8;
+            rest /= 4;
+        }
+        assert_eq!(
+            evaluated.next_state(&states),
+            lookup.next_state(&states),
+            "configuration {:?}",
+            states
+        );
+    }
+}